@@ -0,0 +1,152 @@
+use crate::feedback::{Feedback, Pattern};
+use crate::filter::narrow_candidates;
+use crate::game::GameState;
+use crate::matrix::PatternMatrix;
+use crate::solver::{Options, Outcome, SolveResult, Turn};
+use crate::strategy::{Buckets, Strategy};
+use crate::words::WordLists;
+
+/// host that never commits to an answer, like absurdle: every guess gets
+/// the feedback that leaves the most answers possible
+#[derive(Debug, Clone)]
+pub struct Absurdle {
+    pub state: GameState,
+    /// answers that still fit every pattern given so far
+    pub candidates: Vec<usize>,
+}
+
+impl Absurdle {
+    /// starts a game where every answer is still possible
+    pub fn new(words: &WordLists, state: GameState) -> Absurdle {
+        Absurdle {
+            state,
+            candidates: (0..words.answers.len()).collect(),
+        }
+    }
+
+    /// answers the guess in `matrix` row `guess` with the worst pattern for
+    /// the guesser and narrows the candidates to match it
+    pub fn guess(&mut self, words: &WordLists, matrix: &PatternMatrix, guess: usize) -> Pattern {
+        let pattern = worst_pattern(words, matrix, guess, &self.candidates);
+        self.candidates = narrow_candidates(matrix, &self.candidates, guess, pattern);
+        self.state.push(words.guesses[guess].clone(), pattern);
+        pattern
+    }
+}
+
+/// the pattern for `guess` shared by the most candidates, preferring fewer
+/// greens and then fewer yellows on ties so as little as possible is given
+/// away
+///
+/// panics if there are no candidates
+pub fn worst_pattern(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    guess: usize,
+    candidates: &[usize],
+) -> Pattern {
+    let mut buckets = Buckets::new(matrix);
+    buckets.fill(words, matrix, guess, candidates);
+    let length = matrix.word_length();
+    let hints = |code| {
+        let pattern = Pattern::from_code(code, length).expect("code from matrix");
+        let count = |kind| {
+            pattern
+                .feedback()
+                .into_iter()
+                .filter(|&f| f == kind)
+                .count()
+        };
+        (count(Feedback::Green), count(Feedback::Yellow))
+    };
+    let (code, _, _) = buckets
+        .iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| hints(b.0).cmp(&hints(a.0))))
+        .expect("no candidates");
+    Pattern::from_code(code, length).expect("code from matrix")
+}
+
+/// plays a strategy against an absurdle host until it pins the host down or
+/// runs out of turns
+///
+/// the result's target is the word the host was left with, or one it could
+/// still have picked if the strategy lost
+pub fn solve_absurdle(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    strategy: &dyn Strategy,
+    options: &Options,
+) -> SolveResult {
+    let mut host = Absurdle::new(words, GameState::with_mode(options.mode));
+    let mut turns = Vec::new();
+    loop {
+        let turn = host.state.turn();
+        let guess = strategy.next_guess(&host.state, words, matrix, &host.candidates);
+        let pattern = host.guess(words, matrix, guess);
+        turns.push(Turn {
+            guess: words.guesses[guess].clone(),
+            pattern,
+            remaining: host.candidates.len(),
+        });
+        let outcome = if pattern.is_win() {
+            Outcome::Solved
+        } else if turn >= options.max_turns {
+            Outcome::OutOfTurns
+        } else {
+            continue;
+        };
+        return SolveResult {
+            target: words.answers[host.candidates[0]].clone(),
+            outcome,
+            turns,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(answers: &[&str]) -> WordLists {
+        let answers = answers.iter().map(|w| w.to_string()).collect();
+        WordLists::new(answers, vec!["crane".to_string()])
+    }
+
+    fn worst(answers: &[&str]) -> String {
+        let words = words(answers);
+        let matrix = PatternMatrix::new(&words.guesses, &words.answers);
+        let guess = words.guess_index("crane").unwrap();
+        let candidates: Vec<usize> = (0..words.answers.len()).collect();
+        worst_pattern(&words, &matrix, guess, &candidates).to_string()
+    }
+
+    #[test]
+    fn picks_the_largest_bucket() {
+        // cramp and crane would give away greens, but three answers share
+        // the all black pattern
+        assert_eq!(
+            worst(&["cramp", "moist", "lumpy", "dully", "crane"]),
+            "bbbbb"
+        );
+        assert_eq!(worst(&["cramp", "crass", "craft", "moist"]), "gggbb");
+    }
+
+    #[test]
+    fn ties_prefer_fewer_greens_then_fewer_yellows() {
+        assert_eq!(worst(&["cloud", "scour"]), "yybbb");
+        assert_eq!(worst(&["scour", "moist"]), "bbbbb");
+        assert_eq!(worst(&["cloud", "crane"]), "gbbbb");
+    }
+
+    #[test]
+    fn host_narrows_to_the_pattern_it_gives() {
+        let words = words(&["cramp", "moist", "lumpy", "dully", "crane"]);
+        let matrix = PatternMatrix::new(&words.guesses, &words.answers);
+        let mut host = Absurdle::new(&words, GameState::new());
+        let guess = words.guess_index("crane").unwrap();
+        let pattern = host.guess(&words, &matrix, guess);
+        assert_eq!(pattern.to_string(), "bbbbb");
+        assert_eq!(host.candidates, vec![1, 2, 3]);
+        assert_eq!(host.state.turn(), 2);
+    }
+}
@@ -0,0 +1,343 @@
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::game::{GameState, Mode};
+use crate::matrix::PatternMatrix;
+use crate::solver::{solve, Options, Outcome, SolveResult};
+use crate::strategy::Strategy;
+use crate::words::WordLists;
+
+/// number of targets a thread claims at a time
+const CHUNK: usize = 16;
+
+/// solves every answer as a target and returns the result of each solve, in
+/// the same order as `words.answers`
+///
+/// targets are spread across `threads` threads (all cores if 0); each solve
+/// is independent so the results don't depend on scheduling
+pub fn benchmark(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    strategy: &(dyn Strategy + Sync),
+    options: &Options,
+    threads: usize,
+) -> Vec<SolveResult> {
+    let threads = if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    };
+    let opening = Opening::new(words, matrix, strategy, options);
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<SolveResult>> = vec![None; words.answers.len()];
+
+    thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut solved: Vec<(usize, SolveResult)> = Vec::new();
+                    loop {
+                        let start = next.fetch_add(CHUNK, Ordering::Relaxed);
+                        if start >= words.answers.len() {
+                            break;
+                        }
+                        for (i, target) in words.answers.iter().enumerate().skip(start).take(CHUNK)
+                        {
+                            solved.push((i, solve(words, matrix, target, &opening, options)));
+                        }
+                    }
+                    solved
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (i, result) in worker.join().expect("benchmark thread panicked") {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.expect("every target solved"))
+        .collect()
+}
+
+/// a strategy with its first guess worked out once up front, since every
+/// solve in a benchmark opens from the same empty game
+struct Opening<'a> {
+    strategy: &'a (dyn Strategy + Sync),
+    first: usize,
+}
+
+impl Opening<'_> {
+    fn new<'a>(
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        strategy: &'a (dyn Strategy + Sync),
+        options: &Options,
+    ) -> Opening<'a> {
+        let candidates: Vec<usize> = (0..words.answers.len()).collect();
+        let state = GameState::with_mode(options.mode);
+        Opening {
+            strategy,
+            first: strategy.next_guess(&state, words, matrix, &candidates),
+        }
+    }
+}
+
+impl Strategy for Opening<'_> {
+    fn next_guess(
+        &self,
+        state: &GameState,
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        candidates: &[usize],
+    ) -> usize {
+        if state.history.is_empty() {
+            self.first
+        } else {
+            self.strategy.next_guess(state, words, matrix, candidates)
+        }
+    }
+}
+
+/// percentiles of solved turn counts included in a report
+const PERCENTILES: [u32; 4] = [50, 90, 95, 99];
+
+/// summary of a benchmark run
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// rules every guess followed
+    pub mode: Mode,
+    /// number of targets attempted
+    pub targets: usize,
+    /// number of targets solved for each turn count, starting at turn 1
+    pub histogram: Vec<usize>,
+    /// targets that couldn't be solved, in word list order
+    pub failed: Vec<String>,
+    /// failed targets that ran out of turns
+    pub out_of_turns: usize,
+    /// failed targets that no answer fit, because they aren't in the list
+    pub no_candidates: usize,
+    /// mean turns over solved targets only
+    pub average: f64,
+    /// most turns any solved target took
+    pub worst: u32,
+    /// (percentile, turns) pairs over solved targets
+    pub percentiles: Vec<(u32, u32)>,
+    /// wall clock time of the run
+    pub elapsed: Duration,
+}
+
+impl Report {
+    /// summarizes the results returned by [`benchmark`]
+    pub fn new(results: &[SolveResult], options: &Options, elapsed: Duration) -> Report {
+        let mut solved: Vec<u32> = results
+            .iter()
+            .filter(|r| r.is_solved())
+            .map(SolveResult::turn_count)
+            .collect();
+        solved.sort_unstable();
+
+        let mut histogram = vec![0; options.max_turns as usize];
+        for &turn in &solved {
+            histogram[turn as usize - 1] += 1;
+        }
+        let failed = results
+            .iter()
+            .filter(|r| !r.is_solved())
+            .map(|r| r.target.clone())
+            .collect();
+        let count = |outcome| results.iter().filter(|r| r.outcome == outcome).count();
+        let average = if solved.is_empty() {
+            0.0
+        } else {
+            solved.iter().sum::<u32>() as f64 / solved.len() as f64
+        };
+
+        Report {
+            mode: options.mode,
+            targets: results.len(),
+            histogram,
+            failed,
+            out_of_turns: count(Outcome::OutOfTurns),
+            no_candidates: count(Outcome::NoCandidates),
+            average,
+            worst: solved.last().copied().unwrap_or(0),
+            percentiles: PERCENTILES
+                .iter()
+                .map(|&p| (p, percentile(&solved, p)))
+                .collect(),
+            elapsed,
+        }
+    }
+
+    /// number of targets solved
+    pub fn solved(&self) -> usize {
+        self.targets - self.failed.len()
+    }
+
+    /// report as a JSON object
+    pub fn to_json(&self) -> String {
+        let percentiles: Map<String, Value> = self
+            .percentiles
+            .iter()
+            .map(|(p, t)| (format!("p{}", p), json!(t)))
+            .collect();
+        json!({
+            "mode": self.mode.to_string(),
+            "targets": self.targets,
+            "solved": self.solved(),
+            "unsolved": self.failed.len(),
+            "out_of_turns": self.out_of_turns,
+            "no_candidates": self.no_candidates,
+            "average": (self.average * 1e4).round() / 1e4,
+            "worst": self.worst,
+            "percentiles": percentiles,
+            "histogram": self.histogram,
+            "failed": self.failed,
+            "elapsed_ms": self.elapsed.as_millis() as u64,
+        })
+        .to_string()
+    }
+
+    /// report as `metric,value` CSV rows, with failed targets space separated
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![
+            "metric,value".to_string(),
+            format!("mode,{}", self.mode),
+            format!("targets,{}", self.targets),
+            format!("solved,{}", self.solved()),
+            format!("unsolved,{}", self.failed.len()),
+            format!("out_of_turns,{}", self.out_of_turns),
+            format!("no_candidates,{}", self.no_candidates),
+            format!("average,{:.4}", self.average),
+            format!("worst,{}", self.worst),
+        ];
+        for (p, t) in &self.percentiles {
+            rows.push(format!("p{},{}", p, t));
+        }
+        for (i, n) in self.histogram.iter().enumerate() {
+            rows.push(format!("turn_{},{}", i + 1, n));
+        }
+        rows.push(format!("failed,{}", self.failed.join(" ")));
+        rows.push(format!("elapsed_ms,{}", self.elapsed.as_millis()));
+        rows.join("\n")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let largest = self.histogram.iter().copied().max().unwrap_or(0).max(1);
+        writeln!(f, "mode: {}", self.mode)?;
+        writeln!(f, "turn distribution:")?;
+        for (i, n) in self.histogram.iter().enumerate() {
+            let bar = "#".repeat((n * 40).div_ceil(largest));
+            writeln!(f, "  {}: {:>6} {}", i + 1, n, bar)?;
+        }
+        writeln!(f, "solved: {} / {}", self.solved(), self.targets)?;
+        writeln!(f, "average solve turn: {:.4}", self.average)?;
+        writeln!(f, "worst solve turn: {}", self.worst)?;
+        for (p, t) in &self.percentiles {
+            writeln!(f, "p{}: {}", p, t)?;
+        }
+        writeln!(
+            f,
+            "unable to solve: {} (out of turns: {}, no candidates: {})",
+            self.failed.len(),
+            self.out_of_turns,
+            self.no_candidates
+        )?;
+        if !self.failed.is_empty() {
+            writeln!(f, "failed: {}", self.failed.join(" "))?;
+        }
+        write!(f, "took {:.2?}", self.elapsed)
+    }
+}
+
+/// nearest-rank percentile of sorted values, 0 if there are none
+fn percentile(sorted: &[u32], p: u32) -> u32 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p as usize * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::Pattern;
+    use crate::solver::Turn;
+
+    fn result(target: &str, outcome: Outcome, turns: usize) -> SolveResult {
+        let turn = Turn {
+            guess: target.to_string(),
+            pattern: Pattern::win(5),
+            remaining: 1,
+        };
+        SolveResult {
+            target: target.to_string(),
+            outcome,
+            turns: vec![turn; turns],
+        }
+    }
+
+    fn report() -> Report {
+        let results = [
+            result("about", Outcome::Solved, 1),
+            result("other", Outcome::Solved, 2),
+            result("which", Outcome::Solved, 2),
+            result("zzzzz", Outcome::NoCandidates, 3),
+            result("their", Outcome::Solved, 3),
+            result("jazzy", Outcome::OutOfTurns, 6),
+            result("there", Outcome::Solved, 6),
+        ];
+        Report::new(&results, &Options::default(), Duration::from_millis(12))
+    }
+
+    #[test]
+    fn counts_solves_and_failures() {
+        let report = report();
+        assert_eq!(report.targets, 7);
+        assert_eq!(report.solved(), 5);
+        assert_eq!(report.histogram, vec![1, 2, 1, 0, 0, 1]);
+        assert_eq!(report.failed, vec!["zzzzz", "jazzy"]);
+        assert_eq!(report.out_of_turns, 1);
+        assert_eq!(report.no_candidates, 1);
+    }
+
+    #[test]
+    fn averages_over_solved_targets_only() {
+        let report = report();
+        assert_eq!(report.average, 2.8);
+        assert_eq!(report.worst, 6);
+        assert_eq!(report.percentiles, vec![(50, 2), (90, 6), (95, 6), (99, 6)]);
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let values: Vec<u32> = (1..=100).collect();
+        assert_eq!(percentile(&values, 50), 50);
+        assert_eq!(percentile(&values, 99), 99);
+        assert_eq!(percentile(&values[..3], 50), 2);
+        assert_eq!(percentile(&values[..1], 1), 1);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn json_report() {
+        let json: Value = serde_json::from_str(&report().to_json()).unwrap();
+        assert_eq!(json["mode"], "normal");
+        assert_eq!(json["solved"], 5);
+        assert_eq!(json["average"], 2.8);
+        assert_eq!(json["percentiles"]["p90"], 6);
+        assert_eq!(json["histogram"], json!([1, 2, 1, 0, 0, 1]));
+        assert_eq!(json["failed"], json!(["zzzzz", "jazzy"]));
+        assert_eq!(json["elapsed_ms"], 12);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::feedback::{get_pattern, Pattern};
+use crate::filter::narrow_candidates;
+use crate::game::{GameState, Mode};
+use crate::matrix::PatternMatrix;
+use crate::solver::{Options, Outcome, SolveResult, Turn};
+use crate::strategy::{Buckets, Strategy};
+use crate::words::WordLists;
+
+/// one of several words solved at once with shared guesses, as in dordle,
+/// quordle and octordle
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub state: GameState,
+    /// answers that still fit every pattern this board has shown
+    pub candidates: Vec<usize>,
+}
+
+impl Board {
+    /// starts a board where every answer is still possible
+    pub fn new(words: &WordLists, mode: Mode) -> Board {
+        Board {
+            state: GameState::with_mode(mode),
+            candidates: (0..words.answers.len()).collect(),
+        }
+    }
+
+    /// records the pattern this board showed for the guess in `matrix` row
+    /// `guess`
+    pub fn play(
+        &mut self,
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        guess: usize,
+        pattern: Pattern,
+    ) {
+        self.candidates = narrow_candidates(matrix, &self.candidates, guess, pattern);
+        self.state.push(words.guesses[guess].clone(), pattern);
+    }
+
+    /// determines if the board needs no more guesses, because it was solved
+    /// or no answer fits it
+    pub fn is_done(&self) -> bool {
+        self.state.is_won() || self.candidates.is_empty()
+    }
+}
+
+/// picks a guess that helps across every unfinished board
+///
+/// a board down to one candidate gets it guessed straight away. otherwise
+/// each board's pick under `strategy` is tried on all of the boards, and the
+/// one with the most expected information in total wins
+///
+/// panics if every board is done
+pub fn next_board_guess(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    strategy: &dyn Strategy,
+    boards: &[Board],
+) -> usize {
+    let open: Vec<&Board> = boards.iter().filter(|b| !b.is_done()).collect();
+    if let Some(board) = open.iter().find(|b| b.candidates.len() == 1) {
+        return board.candidates[0];
+    }
+
+    let picks: Vec<usize> = open
+        .iter()
+        .map(|b| strategy.next_guess(&b.state, words, matrix, &b.candidates))
+        .collect();
+    let mut buckets = Buckets::new(matrix);
+    let mut best = *picks.first().expect("every board is done");
+    let mut best_entropy = f64::MIN;
+    for &guess in &picks {
+        let entropy: f64 = open
+            .iter()
+            .map(|b| {
+                buckets.fill(words, matrix, guess, &b.candidates);
+                buckets.entropy()
+            })
+            .sum();
+        if entropy > best_entropy {
+            best_entropy = entropy;
+            best = guess;
+        }
+    }
+    best
+}
+
+/// solves one board per target with shared guesses until every board is
+/// done or the turns run out, returning a result per board
+pub fn solve_boards(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    targets: &[String],
+    strategy: &dyn Strategy,
+    options: &Options,
+) -> Vec<SolveResult> {
+    let mut boards: Vec<Board> = targets
+        .iter()
+        .map(|_| Board::new(words, options.mode))
+        .collect();
+    let mut results: Vec<SolveResult> = targets
+        .iter()
+        .map(|target| SolveResult {
+            target: target.clone(),
+            outcome: Outcome::OutOfTurns,
+            turns: Vec::new(),
+        })
+        .collect();
+
+    for _ in 0..options.max_turns {
+        if boards.iter().all(Board::is_done) {
+            break;
+        }
+        let guess = next_board_guess(words, matrix, strategy, &boards);
+        for (i, board) in boards.iter_mut().enumerate() {
+            if board.is_done() {
+                continue;
+            }
+            let pattern = get_pattern(&words.guesses[guess], &targets[i]);
+            board.play(words, matrix, guess, pattern);
+            results[i].turns.push(Turn {
+                guess: words.guesses[guess].clone(),
+                pattern,
+                remaining: board.candidates.len(),
+            });
+            if pattern.is_win() {
+                results[i].outcome = Outcome::Solved;
+            } else if board.candidates.is_empty() {
+                results[i].outcome = Outcome::NoCandidates;
+            }
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{Entropy, Frequency};
+    use crate::words::bundled_words;
+
+    fn small_words() -> WordLists {
+        let answers = bundled_words(u64::MAX)
+            .answers
+            .into_iter()
+            .step_by(9)
+            .collect();
+        WordLists::single(answers)
+    }
+
+    #[test]
+    fn board_with_one_candidate_is_guessed_first() {
+        let words = small_words();
+        let matrix = PatternMatrix::new(&words.guesses, &words.answers);
+        let open = Board::new(&words, Mode::Normal);
+        let mut down_to_one = Board::new(&words, Mode::Normal);
+        down_to_one.candidates = vec![5];
+        let boards = [open.clone(), down_to_one];
+        assert_eq!(next_board_guess(&words, &matrix, &Frequency, &boards), 5);
+
+        // a solved board is skipped even though it has one candidate left
+        let mut solved = Board::new(&words, Mode::Normal);
+        solved.play(&words, &matrix, 7, Pattern::win(5));
+        solved.candidates = vec![7];
+        let boards = [solved, open];
+        assert_eq!(next_board_guess(&words, &matrix, &Frequency, &boards), 0);
+    }
+
+    #[test]
+    fn boards_share_guesses_until_each_is_solved() {
+        let words = small_words();
+        let matrix = PatternMatrix::new(&words.guesses, &words.answers);
+        let targets: Vec<String> = words.answers.iter().step_by(40).take(4).cloned().collect();
+        let options = Options {
+            max_turns: 9,
+            ..Options::default()
+        };
+        let results = solve_boards(&words, &matrix, &targets, &Entropy, &options);
+        assert_eq!(results.len(), 4);
+        let full = results.iter().max_by_key(|r| r.turns.len()).unwrap();
+        for (result, target) in results.iter().zip(&targets) {
+            assert_eq!(&result.target, target);
+            assert!(result.is_solved(), "{} not solved", target);
+            assert_eq!(&result.turns.last().unwrap().guess, target);
+            for (turn, shared) in result.turns.iter().zip(&full.turns) {
+                assert_eq!(turn.guess, shared.guess);
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+/// one line summary of the commands, shown by :help
+pub const HELP: &str = "commands: :undo, :history, :reset, :top [N], :candidates, :help";
+
+/// number of likely words :top shows without a count
+pub const DEFAULT_TOP: usize = 10;
+
+/// commands accepted in place of a hint in play, each starting with ':'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// take back the last hint
+    Undo,
+    /// list the guesses and patterns so far
+    History,
+    /// start the game over
+    Reset,
+    /// show the N most likely remaining words
+    Top(usize),
+    /// show every remaining word
+    Candidates,
+    /// show the list of commands
+    Help,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    /// parses a command such as ":undo" or ":top 5"
+    fn from_str(s: &str) -> Result<Command, String> {
+        let s = s.trim();
+        let mut parts = s.strip_prefix(':').unwrap_or(s).split_whitespace();
+        let command = match (parts.next().unwrap_or_default(), parts.next()) {
+            ("undo", None) => Command::Undo,
+            ("history", None) => Command::History,
+            ("reset", None) => Command::Reset,
+            ("top", None) => Command::Top(DEFAULT_TOP),
+            ("top", Some(n)) => match n.parse() {
+                Ok(n) => Command::Top(n),
+                Err(_) => return Err(format!("invalid count {:?}", n)),
+            },
+            ("candidates", None) => Command::Candidates,
+            ("help", None) => Command::Help,
+            _ => return Err(format!("unknown command {:?}, {}", s, HELP)),
+        };
+        match parts.next() {
+            Some(_) => Err(format!("unexpected arguments in {:?}", s)),
+            None => Ok(command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(":undo".parse(), Ok(Command::Undo));
+        assert_eq!(" :history\n".parse(), Ok(Command::History));
+        assert_eq!(":reset".parse(), Ok(Command::Reset));
+        assert_eq!(":candidates".parse(), Ok(Command::Candidates));
+        assert_eq!(":help".parse(), Ok(Command::Help));
+    }
+
+    #[test]
+    fn parses_top_with_an_optional_count() {
+        assert_eq!(":top".parse(), Ok(Command::Top(DEFAULT_TOP)));
+        assert_eq!(":top 5".parse(), Ok(Command::Top(5)));
+        assert_eq!(
+            ":top x".parse::<Command>(),
+            Err("invalid count \"x\"".to_string())
+        );
+        assert!(":top 5 6".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_commands() {
+        let err = ":redo".parse::<Command>().unwrap_err();
+        assert!(err.starts_with("unknown command \":redo\""), "{}", err);
+        assert!(err.ends_with(HELP), "{}", err);
+        assert!(":undo 2".parse::<Command>().is_err());
+        assert!(":".parse::<Command>().is_err());
+    }
+}
@@ -0,0 +1,310 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::matrix::pattern_code;
+
+/// number of letters in a word unless told otherwise
+pub const DEFAULT_LENGTH: usize = 5;
+
+/// fewest letters a word may have
+pub const MIN_LENGTH: usize = 3;
+
+/// most letters a word may have, so every pattern code fits in a u32
+pub const MAX_LENGTH: usize = 12;
+
+/// colour of the feedback for a single letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feedback {
+    /// letter is not in the word (or not as many times as guessed)
+    Black,
+    /// letter is in the word but in a different position
+    Yellow,
+    /// letter is in the word at this position
+    Green,
+}
+
+impl Feedback {
+    /// character used to type and print this feedback
+    pub fn to_char(self) -> char {
+        match self {
+            Feedback::Black => 'b',
+            Feedback::Yellow => 'y',
+            Feedback::Green => 'g',
+        }
+    }
+
+    /// base-3 digit used in a pattern code
+    fn digit(self) -> u32 {
+        match self {
+            Feedback::Black => 0,
+            Feedback::Yellow => 1,
+            Feedback::Green => 2,
+        }
+    }
+
+    fn from_digit(digit: u32) -> Feedback {
+        match digit {
+            0 => Feedback::Black,
+            1 => Feedback::Yellow,
+            _ => Feedback::Green,
+        }
+    }
+}
+
+impl TryFrom<char> for Feedback {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Feedback, ParseError> {
+        match c.to_ascii_lowercase() {
+            'b' => Ok(Feedback::Black),
+            'y' => Ok(Feedback::Yellow),
+            'g' => Ok(Feedback::Green),
+            _ => Err(ParseError::Feedback(c)),
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// feedback for a whole guess, stored as a base-3 number where the first
+/// letter is the least significant digit, along with the word length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pattern {
+    pub(crate) code: u32,
+    pub(crate) len: u8,
+}
+
+impl Pattern {
+    /// number of distinct patterns for words of `len` letters
+    pub fn count(len: usize) -> usize {
+        3usize.pow(len as u32)
+    }
+
+    /// pattern where every letter of a `len` letter word is green
+    pub fn win(len: usize) -> Pattern {
+        Pattern {
+            code: Pattern::count(len) as u32 - 1,
+            len: len as u8,
+        }
+    }
+
+    /// builds a pattern from one feedback per letter
+    ///
+    /// panics if there are more than `MAX_LENGTH` letters
+    pub fn new(feedback: &[Feedback]) -> Pattern {
+        assert!(feedback.len() <= MAX_LENGTH, "word too long");
+        Pattern {
+            code: feedback
+                .iter()
+                .rev()
+                .fold(0, |code, f| code * 3 + f.digit()),
+            len: feedback.len() as u8,
+        }
+    }
+
+    /// gets the pattern with the given code for `len` letter words, if the
+    /// code is in range
+    pub fn from_code(code: u32, len: usize) -> Option<Pattern> {
+        if len <= MAX_LENGTH && (code as usize) < Pattern::count(len) {
+            Some(Pattern {
+                code,
+                len: len as u8,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// compact base-3 code of the pattern
+    pub fn code(self) -> u32 {
+        self.code
+    }
+
+    /// number of letters the pattern covers
+    pub fn length(self) -> usize {
+        self.len as usize
+    }
+
+    /// feedback for the letter at `position`
+    pub fn get(self, position: usize) -> Feedback {
+        Feedback::from_digit(self.code / 3u32.pow(position as u32) % 3)
+    }
+
+    /// feedback for every letter, in order
+    pub fn feedback(self) -> Vec<Feedback> {
+        (0..self.length()).map(|pos| self.get(pos)).collect()
+    }
+
+    /// determines if every letter is green
+    pub fn is_win(self) -> bool {
+        self == Pattern::win(self.length())
+    }
+
+    /// pairs each letter of the guess with its feedback
+    pub fn hints(self, guess: &str) -> Vec<Hint> {
+        guess
+            .chars()
+            .zip(self.feedback())
+            .enumerate()
+            .map(|(position, (letter, kind))| Hint {
+                letter,
+                position,
+                kind,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ParseError;
+
+    /// parses a string such as "ggybb"
+    fn from_str(s: &str) -> Result<Pattern, ParseError> {
+        let chars: Vec<char> = s.trim().chars().collect();
+        if !(MIN_LENGTH..=MAX_LENGTH).contains(&chars.len()) {
+            return Err(ParseError::Unsupported(chars.len()));
+        }
+        let feedback = chars
+            .into_iter()
+            .map(Feedback::try_from)
+            .collect::<Result<Vec<Feedback>, ParseError>>()?;
+        Ok(Pattern::new(&feedback))
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for feedback in self.feedback() {
+            write!(f, "{}", feedback)?;
+        }
+        Ok(())
+    }
+}
+
+/// errors from parsing feedback, patterns and game rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// input had a different number of letters than the game's words
+    Length { expected: usize, got: usize },
+    /// input had fewer than `MIN_LENGTH` or more than `MAX_LENGTH` letters
+    Unsupported(usize),
+    /// character was not one of 'g', 'y' or 'b'
+    Feedback(char),
+    /// word contained something other than a lowercase letter
+    Word(String),
+    /// row was not a word followed by a pattern
+    Row(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Length { expected, got } => {
+                write!(f, "expected {} letters, got {}", expected, got)
+            }
+            ParseError::Unsupported(n) => write!(
+                f,
+                "words must have {} to {} letters, got {}",
+                MIN_LENGTH, MAX_LENGTH, n
+            ),
+            ParseError::Feedback(c) => {
+                write!(f, "invalid feedback {:?}, expected 'g', 'y' or 'b'", c)
+            }
+            ParseError::Word(w) => write!(f, "invalid word {:?}", w),
+            ParseError::Row(r) => write!(f, "invalid row {:?}, expected \"<guess> <pattern>\"", r),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// stores hint information
+#[derive(Debug)]
+pub struct Hint {
+    pub letter: char,
+    pub position: usize,
+    pub kind: Feedback,
+}
+
+/// gets the feedback pattern for the provided guess against the target word,
+/// both lowercase words of the same length, scored by
+/// [`pattern_code`](crate::matrix::pattern_code)
+pub fn get_pattern(guess: &str, target: &str) -> Pattern {
+    assert_eq!(guess.len(), target.len(), "words differ in length");
+    let code = pattern_code(guess.as_bytes(), target.as_bytes());
+    Pattern::from_code(code, guess.len()).expect("word too long")
+}
+
+/// gets a list of hints for the provided guess against the target word
+pub fn get_hints(guess: &str, target: &str) -> Vec<Hint> {
+    get_pattern(guess, target).hints(guess)
+}
+
+/// determines if all hints are green
+pub fn is_winner(hints: &[Hint]) -> bool {
+    hints.iter().all(|hint| hint.kind == Feedback::Green)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(guess: &str, target: &str) -> String {
+        get_pattern(guess, target).to_string()
+    }
+
+    #[test]
+    fn repeated_guess_letter_is_yellow_once() {
+        assert_eq!(pattern("speed", "abide"), "bbyby");
+    }
+
+    #[test]
+    fn green_uses_up_a_repeated_letter() {
+        assert_eq!(pattern("eerie", "there"), "ybybg");
+        assert_eq!(pattern("there", "eerie"), "bbyyg");
+        assert_eq!(pattern("eerie", "eerie"), "ggggg");
+    }
+
+    #[test]
+    fn pattern_round_trips_through_its_code() {
+        use Feedback::*;
+        let feedback = [Green, Green, Yellow, Black, Black];
+        let pattern = Pattern::new(&feedback);
+        assert_eq!(pattern.feedback(), feedback);
+        assert_eq!(pattern.get(2), Yellow);
+        assert_eq!(Pattern::from_code(pattern.code(), 5), Some(pattern));
+        assert_eq!(Pattern::from_code(Pattern::count(5) as u32, 5), None);
+        assert!(Pattern::win(5).is_win());
+    }
+
+    #[test]
+    fn parses_and_displays_patterns() {
+        let pattern: Pattern = "ggybb".parse().unwrap();
+        assert_eq!(pattern.length(), 5);
+        assert_eq!(pattern.get(0), Feedback::Green);
+        assert_eq!(pattern.to_string(), "ggybb");
+        assert_eq!(" GGYBB\n".parse(), Ok(pattern));
+    }
+
+    #[test]
+    fn rejects_bad_patterns() {
+        assert_eq!("ggxbb".parse::<Pattern>(), Err(ParseError::Feedback('x')));
+        assert_eq!("gg".parse::<Pattern>(), Err(ParseError::Unsupported(2)));
+        let long = "b".repeat(MAX_LENGTH + 1);
+        assert_eq!(
+            long.parse::<Pattern>(),
+            Err(ParseError::Unsupported(MAX_LENGTH + 1))
+        );
+    }
+
+    #[test]
+    fn missing_letters_are_black() {
+        assert_eq!(pattern("crane", "moult"), "bbbbb");
+        assert!(get_pattern("crane", "crane").is_win());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::feedback::{get_pattern, Feedback, Hint, Pattern};
+use crate::game::GameState;
+use crate::matrix::PatternMatrix;
+use crate::words::WordLists;
+
+/// letter count and position rules derived from a single row of hints
+#[derive(Debug, Default)]
+pub struct Constraints {
+    /// fewest times each letter must appear in the word
+    min_count: HashMap<char, usize>,
+    /// most times each letter may appear in the word
+    max_count: HashMap<char, usize>,
+    /// letter each position must hold, if known
+    allowed: Vec<Option<char>>,
+    /// letters each position must not hold
+    denied: Vec<HashSet<char>>,
+}
+
+impl Constraints {
+    /// builds constraints from a full row of hints
+    ///
+    /// a black letter only caps the count at the number of green/yellow
+    /// copies of that letter in the same row, so "eerie"-style feedback
+    /// doesn't rule out the answer
+    pub fn from_hints(hints: &[Hint]) -> Constraints {
+        let len = hints.iter().map(|h| h.position + 1).max().unwrap_or(0);
+        let mut constraints = Constraints {
+            allowed: vec![None; len],
+            denied: vec![HashSet::new(); len],
+            ..Default::default()
+        };
+
+        for hint in hints {
+            match hint.kind {
+                Feedback::Green => constraints.allowed[hint.position] = Some(hint.letter),
+                Feedback::Yellow | Feedback::Black => {
+                    constraints.denied[hint.position].insert(hint.letter);
+                }
+            }
+            if hint.kind != Feedback::Black {
+                *constraints.min_count.entry(hint.letter).or_insert(0) += 1;
+            }
+        }
+
+        for hint in hints {
+            if hint.kind == Feedback::Black {
+                let found = constraints.min_count.get(&hint.letter).copied();
+                constraints
+                    .max_count
+                    .insert(hint.letter, found.unwrap_or(0));
+            }
+        }
+
+        constraints
+    }
+
+    /// determines if a word satisfies every constraint
+    pub fn matches(&self, word: &str) -> bool {
+        let letters: Vec<char> = word.chars().collect();
+        for (pos, allowed) in self.allowed.iter().enumerate() {
+            if let Some(c) = allowed {
+                if letters.get(pos) != Some(c) {
+                    return false;
+                }
+            }
+        }
+        for (pos, denied) in self.denied.iter().enumerate() {
+            if letters.get(pos).is_some_and(|c| denied.contains(c)) {
+                return false;
+            }
+        }
+        for (letter, min) in &self.min_count {
+            if letters.iter().filter(|c| *c == letter).count() < *min {
+                return false;
+            }
+        }
+        for (letter, max) in &self.max_count {
+            if letters.iter().filter(|c| *c == letter).count() > *max {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// narrows down potential guesses based on provided hints
+pub fn narrow_guesses(words: Vec<String>, hints: &[Hint]) -> Vec<String> {
+    let constraints = Constraints::from_hints(hints);
+    words
+        .into_iter()
+        .filter(|word| constraints.matches(word))
+        .collect()
+}
+
+/// keeps the candidate answers that would have given `pattern` for the guess
+pub fn narrow_candidates(
+    matrix: &PatternMatrix,
+    candidates: &[usize],
+    guess: usize,
+    pattern: Pattern,
+) -> Vec<usize> {
+    let row = matrix.row(guess);
+    candidates
+        .iter()
+        .copied()
+        .filter(|&answer| row.get(answer) == pattern.code())
+        .collect()
+}
+
+/// keeps the candidate answers that would have given `pattern` for a guess
+/// given as a word, scoring it on the fly if it isn't a row of `matrix`
+pub fn narrow_by_word(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    candidates: &[usize],
+    guess: &str,
+    pattern: Pattern,
+) -> Vec<usize> {
+    match words.guess_index(guess) {
+        Some(row) => narrow_candidates(matrix, candidates, row, pattern),
+        None => candidates
+            .iter()
+            .copied()
+            .filter(|&answer| get_pattern(guess, &words.answers[answer]) == pattern)
+            .collect(),
+    }
+}
+
+/// the answers that fit every guess and pattern of a game so far
+pub fn replay(words: &WordLists, matrix: &PatternMatrix, state: &GameState) -> Vec<usize> {
+    let mut candidates: Vec<usize> = (0..words.answers.len()).collect();
+    for (guess, pattern) in &state.history {
+        candidates = narrow_by_word(words, matrix, &candidates, guess, *pattern);
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::get_hints;
+    use crate::words::bundled_words;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn black_repeat_caps_letter_count() {
+        let hints = get_hints("eerie", "there");
+        let list = words(&["there", "where", "three", "ether", "eerie", "their"]);
+        assert_eq!(narrow_guesses(list, &hints), words(&["there", "where"]));
+    }
+
+    #[test]
+    fn keeps_exactly_the_words_with_the_same_pattern() {
+        let list = bundled_words(u64::MAX).answers;
+        let sample: Vec<&String> = list.iter().step_by(97).collect();
+        for guess in &sample {
+            for target in &sample {
+                let pattern = get_pattern(guess, target);
+                let expected: Vec<String> = list
+                    .iter()
+                    .filter(|w| get_pattern(guess, w) == pattern)
+                    .cloned()
+                    .collect();
+                let hints = get_hints(guess, target);
+                let narrowed = narrow_guesses(list.clone(), &hints);
+                assert_eq!(narrowed, expected, "{} against {}", guess, target);
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use serde_json::Value;
+use std::fmt;
+use std::io::{self, prelude::*};
+use std::path::Path;
+use std::str::FromStr;
+
+/// layout of a word list file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// one word per line
+    Plain,
+    /// `word<TAB>frequency` per line, like norvig's count_1w.txt
+    Tsv,
+    /// `word,frequency` per line, with an optional header row
+    Csv,
+    /// array of words, or of `[word, frequency]` pairs
+    Json,
+}
+
+impl ListFormat {
+    /// guesses the format from a file extension, ignoring a trailing `.gz`
+    pub fn from_path(path: &Path) -> Option<ListFormat> {
+        let mut path = path.to_path_buf();
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            path.set_extension("");
+        }
+        match path.extension()?.to_str()? {
+            "json" => Some(ListFormat::Json),
+            "csv" => Some(ListFormat::Csv),
+            "tsv" => Some(ListFormat::Tsv),
+            _ => None,
+        }
+    }
+
+    /// guesses the format from the start of the file's contents
+    pub fn sniff(start: &[u8]) -> ListFormat {
+        let text = String::from_utf8_lossy(start);
+        let text = text.trim_start();
+        let first_line = text.lines().next().unwrap_or_default();
+        if text.starts_with('[') {
+            ListFormat::Json
+        } else if first_line.contains('\t') {
+            ListFormat::Tsv
+        } else if first_line.contains(',') {
+            ListFormat::Csv
+        } else {
+            ListFormat::Plain
+        }
+    }
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ListFormat, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" | "txt" => Ok(ListFormat::Plain),
+            "tsv" => Ok(ListFormat::Tsv),
+            "csv" => Ok(ListFormat::Csv),
+            "json" => Ok(ListFormat::Json),
+            _ => Err(format!(
+                "unknown word list format {:?}, expected plain, tsv, csv or json",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ListFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ListFormat::Plain => "plain",
+            ListFormat::Tsv => "tsv",
+            ListFormat::Csv => "csv",
+            ListFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// a word from a list along with its corpus frequency, if the list has one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub word: String,
+    pub count: Option<u64>,
+}
+
+/// reads every entry of a word list in file order
+///
+/// malformed lines are reported as `InvalidData` errors naming the line
+pub fn read_entries(reader: impl BufRead, format: ListFormat) -> io::Result<Vec<Entry>> {
+    match format {
+        ListFormat::Json => read_json(reader),
+        ListFormat::Plain | ListFormat::Tsv | ListFormat::Csv => {
+            let mut entries = Vec::new();
+            for (i, line) in reader.lines().enumerate() {
+                let line = line?;
+                let line = line.trim_end_matches('\r');
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match parse_line(line, format) {
+                    Ok(entry) => entries.push(entry),
+                    Err(_) if i == 0 && format == ListFormat::Csv && is_header(line) => {}
+                    Err(msg) => return Err(invalid(format!("line {}: {}", i + 1, msg))),
+                }
+            }
+            Ok(entries)
+        }
+    }
+}
+
+/// parses a single line of a line based format
+fn parse_line(line: &str, format: ListFormat) -> Result<Entry, String> {
+    let separator = match format {
+        ListFormat::Tsv => '\t',
+        ListFormat::Csv => ',',
+        _ => {
+            let word = line.trim();
+            if word.contains(char::is_whitespace) {
+                return Err(format!("expected one word, got {:?}", word));
+            }
+            return Ok(Entry {
+                word: word.to_string(),
+                count: None,
+            });
+        }
+    };
+
+    let fields: Vec<&str> = line.split(separator).map(str::trim).collect();
+    match fields[..] {
+        [word, count] if !word.is_empty() => match count.parse() {
+            Ok(count) => Ok(Entry {
+                word: word.to_string(),
+                count: Some(count),
+            }),
+            Err(_) => Err(format!("invalid frequency {:?}", count)),
+        },
+        _ => Err(format!(
+            "expected \"word{}frequency\", got {:?}",
+            separator.escape_default(),
+            line
+        )),
+    }
+}
+
+/// whether a csv line names its columns, like `word,frequency`, rather than
+/// being a malformed entry
+fn is_header(line: &str) -> bool {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    fields.len() == 2
+        && fields.iter().all(|field| {
+            !field.is_empty()
+                && field
+                    .chars()
+                    .all(|c| c.is_alphabetic() || c == '_' || c == ' ')
+        })
+}
+
+/// parses a json array of words or `[word, frequency]` pairs
+fn read_json(reader: impl BufRead) -> io::Result<Vec<Entry>> {
+    // serde_json's message already ends with the line and column
+    let values: Vec<Value> = serde_json::from_reader(reader).map_err(|e| invalid(e.to_string()))?;
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| match value {
+            Value::String(word) => Ok(Entry { word, count: None }),
+            Value::Array(pair) => match &pair[..] {
+                [Value::String(word), Value::Number(count)] if count.is_u64() => Ok(Entry {
+                    word: word.to_string(),
+                    count: count.as_u64(),
+                }),
+                _ => Err(invalid(format!(
+                    "element {}: expected [word, frequency]",
+                    i + 1
+                ))),
+            },
+            _ => Err(invalid(format!(
+                "element {}: expected a word or [word, frequency]",
+                i + 1
+            ))),
+        })
+        .collect()
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, count: Option<u64>) -> Entry {
+        Entry {
+            word: word.to_string(),
+            count,
+        }
+    }
+
+    fn read(text: &str, format: ListFormat) -> io::Result<Vec<Entry>> {
+        read_entries(text.as_bytes(), format)
+    }
+
+    #[test]
+    fn reads_plain() {
+        let entries = read("about\r\n\nother\n", ListFormat::Plain).unwrap();
+        assert_eq!(entries, vec![entry("about", None), entry("other", None)]);
+    }
+
+    #[test]
+    fn reads_tsv() {
+        let entries = read("about\t1226\nother\t978\n", ListFormat::Tsv).unwrap();
+        assert_eq!(
+            entries,
+            vec![entry("about", Some(1226)), entry("other", Some(978))]
+        );
+    }
+
+    #[test]
+    fn reads_csv_with_header() {
+        let entries = read("word,count\nabout,1226\nother, 978\n", ListFormat::Csv).unwrap();
+        assert_eq!(
+            entries,
+            vec![entry("about", Some(1226)), entry("other", Some(978))]
+        );
+    }
+
+    #[test]
+    fn malformed_first_csv_line_is_an_error() {
+        let err = read("about,12x\nother,978\n", ListFormat::Csv).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 1: invalid frequency \"12x\"");
+    }
+
+    #[test]
+    fn reads_json() {
+        let entries = read(r#"["about", ["other", 978]]"#, ListFormat::Json).unwrap();
+        assert_eq!(
+            entries,
+            vec![entry("about", None), entry("other", Some(978))]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_line_number() {
+        let err = read("about\t1226\nother 978\n", ListFormat::Tsv).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2: "), "{}", err);
+    }
+
+    #[test]
+    fn sniffs_format_from_contents() {
+        assert_eq!(ListFormat::sniff(b"[\"about\"]"), ListFormat::Json);
+        assert_eq!(ListFormat::sniff(b"about\t1226\n"), ListFormat::Tsv);
+        assert_eq!(ListFormat::sniff(b"word,count\n"), ListFormat::Csv);
+        assert_eq!(ListFormat::sniff(b"about\n"), ListFormat::Plain);
+    }
+}
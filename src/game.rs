@@ -0,0 +1,436 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::feedback::{Feedback, ParseError, Pattern, MAX_LENGTH, MIN_LENGTH};
+
+/// how strictly guesses must follow the hints revealed so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// any allowed word may be guessed
+    #[default]
+    Normal,
+    /// green letters must stay in place and yellow letters must be reused
+    Hard,
+    /// hard mode, and yellow letters can't go back where they were yellow
+    UltraHard,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Mode, String> {
+        match s {
+            "normal" => Ok(Mode::Normal),
+            "hard" => Ok(Mode::Hard),
+            "ultra-hard" => Ok(Mode::UltraHard),
+            _ => Err(format!(
+                "unknown mode {:?}, expected normal, hard or ultra-hard",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Mode::Normal => "normal",
+            Mode::Hard => "hard",
+            Mode::UltraHard => "ultra-hard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// guesses made so far in a game along with the pattern each one got
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameState {
+    pub history: Vec<(String, Pattern)>,
+    /// rules later guesses have to follow
+    pub mode: Mode,
+}
+
+impl GameState {
+    /// creates a game with no guesses
+    pub fn new() -> GameState {
+        GameState::default()
+    }
+
+    /// creates a game with no guesses played under `mode`
+    pub fn with_mode(mode: Mode) -> GameState {
+        GameState {
+            history: Vec::new(),
+            mode,
+        }
+    }
+
+    /// number of the turn about to be played, starting at 1
+    pub fn turn(&self) -> u32 {
+        self.history.len() as u32 + 1
+    }
+
+    /// records a guess and the pattern it got
+    pub fn push(&mut self, guess: String, pattern: Pattern) {
+        self.history.push((guess, pattern));
+    }
+
+    /// takes back the last guess, returning it if there was one
+    pub fn undo(&mut self) -> Option<(String, Pattern)> {
+        self.history.pop()
+    }
+
+    /// forgets every guess, keeping the mode
+    pub fn reset(&mut self) {
+        self.history.clear();
+    }
+
+    /// determines if the last guess was all green
+    pub fn is_won(&self) -> bool {
+        self.history.last().is_some_and(|(_, p)| p.is_win())
+    }
+
+    /// what the game's mode requires of the next guess
+    pub fn rules(&self) -> Rules {
+        Rules::new(self)
+    }
+
+    /// checks the next guess follows the game's mode
+    pub fn check(&self, guess: &str) -> Result<(), Violation> {
+        self.rules().check(guess)
+    }
+}
+
+/// what the hints so far require of the next guess, compiled once so
+/// checking every allowed guess stays cheap
+#[derive(Debug, Clone)]
+pub struct Rules {
+    /// letter each position must keep, from greens
+    greens: Vec<Option<u8>>,
+    /// fewest copies of each letter, from the greens and yellows of a row
+    min_count: [usize; 26],
+    /// letters that were yellow in each position, only set in ultra hard
+    yellows: Vec<[bool; 26]>,
+}
+
+impl Rules {
+    /// compiles the rules for a game, which are empty in normal mode
+    pub fn new(state: &GameState) -> Rules {
+        let mut rules = Rules {
+            greens: Vec::new(),
+            min_count: [0; 26],
+            yellows: Vec::new(),
+        };
+        if state.mode == Mode::Normal {
+            return rules;
+        }
+
+        for (guess, pattern) in &state.history {
+            let length = guess.len();
+            rules.greens.resize(length, None);
+            if state.mode == Mode::UltraHard {
+                rules.yellows.resize(length, [false; 26]);
+            }
+            let mut found = [0; 26];
+            for (pos, letter) in guess.bytes().enumerate() {
+                let i = (letter - b'a') as usize;
+                match pattern.get(pos) {
+                    Feedback::Green => {
+                        rules.greens[pos] = Some(letter);
+                        found[i] += 1;
+                    }
+                    Feedback::Yellow => {
+                        if let Some(yellows) = rules.yellows.get_mut(pos) {
+                            yellows[i] = true;
+                        }
+                        found[i] += 1;
+                    }
+                    Feedback::Black => {}
+                }
+            }
+            for (min, found) in rules.min_count.iter_mut().zip(found) {
+                *min = (*min).max(found);
+            }
+        }
+        rules
+    }
+
+    /// checks a guess against the rules, reporting the first one it breaks
+    pub fn check(&self, guess: &str) -> Result<(), Violation> {
+        let letters = guess.as_bytes();
+        for (position, green) in self.greens.iter().enumerate() {
+            if let Some(letter) = *green {
+                if letters.get(position) != Some(&letter) {
+                    let letter = letter as char;
+                    return Err(Violation::Green { letter, position });
+                }
+            }
+        }
+        for (position, yellows) in self.yellows.iter().enumerate() {
+            if let Some(&letter) = letters.get(position) {
+                if letter.is_ascii_lowercase() && yellows[(letter - b'a') as usize] {
+                    let letter = letter as char;
+                    return Err(Violation::Yellow { letter, position });
+                }
+            }
+        }
+        for (i, &count) in self.min_count.iter().enumerate() {
+            let letter = b'a' + i as u8;
+            if letters.iter().filter(|&&l| l == letter).count() < count {
+                let letter = letter as char;
+                return Err(Violation::Missing { letter, count });
+            }
+        }
+        Ok(())
+    }
+
+    /// determines if a guess follows the rules
+    pub fn allows(&self, guess: &str) -> bool {
+        self.check(guess).is_ok()
+    }
+}
+
+/// a hard mode rule broken by a guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// a green letter wasn't kept in its position
+    Green { letter: char, position: usize },
+    /// a revealed letter wasn't used as many times as it was revealed
+    Missing { letter: char, count: usize },
+    /// a yellow letter went back where it was yellow (ultra hard only)
+    Yellow { letter: char, position: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Green { letter, position } => {
+                write!(f, "letter {} must be {}", position + 1, letter)
+            }
+            Violation::Missing { letter, count: 1 } => write!(f, "guess must contain {}", letter),
+            Violation::Missing { letter, count } => {
+                write!(f, "guess must contain {} {}s", count, letter)
+            }
+            Violation::Yellow { letter, position } => {
+                write!(f, "letter {} can't be {}", position + 1, letter)
+            }
+        }
+    }
+}
+
+impl Error for Violation {}
+
+impl FromStr for GameState {
+    type Err = ParseError;
+
+    /// parses one "<guess> <pattern>" row per line or comma separated entry,
+    /// where every row must have as many letters as the first
+    fn from_str(s: &str) -> Result<GameState, ParseError> {
+        let mut state = GameState::new();
+        let mut length = None;
+        for row in s.split(['\n', ',']) {
+            if row.trim().is_empty() {
+                continue;
+            }
+            let length = *length
+                .get_or_insert_with(|| row.split_whitespace().next().map_or(0, |word| word.len()));
+            let (guess, pattern) = parse_row(row, length)?;
+            state.push(guess, pattern);
+        }
+        Ok(state)
+    }
+}
+
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (guess, pattern)) in self.history.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{} {}", guess, pattern)?;
+        }
+        Ok(())
+    }
+}
+
+/// validates a guess, which must be `length` lowercase letters
+pub fn parse_word(s: &str, length: usize) -> Result<String, ParseError> {
+    let word = s.trim();
+    if !word.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(ParseError::Word(word.to_string()));
+    }
+    check_length(word.len(), length)?;
+    Ok(word.to_string())
+}
+
+/// parses a pattern such as "bygbb", which must have `length` letters
+pub fn parse_pattern(s: &str, length: usize) -> Result<Pattern, ParseError> {
+    let pattern: Pattern = s.parse()?;
+    check_length(pattern.length(), length)?;
+    Ok(pattern)
+}
+
+/// parses what was played on a turn: either just the pattern the suggested
+/// word got, such as "bygbb", or another guess and its pattern, such as
+/// "crane bygbb"
+pub fn parse_played(
+    s: &str,
+    suggested: &str,
+    length: usize,
+) -> Result<(String, Pattern), ParseError> {
+    if s.split_whitespace().count() > 1 {
+        parse_row(s, length)
+    } else {
+        Ok((suggested.to_string(), parse_pattern(s, length)?))
+    }
+}
+
+/// parses a row such as "crane bygbb" for words of `length` letters, in
+/// either case like the pattern
+pub fn parse_row(s: &str, length: usize) -> Result<(String, Pattern), ParseError> {
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    match parts[..] {
+        [guess, pattern] => Ok((
+            parse_word(&guess.to_ascii_lowercase(), length)?,
+            parse_pattern(pattern, length)?,
+        )),
+        _ => Err(ParseError::Row(s.trim().to_string())),
+    }
+}
+
+fn check_length(got: usize, expected: usize) -> Result<(), ParseError> {
+    if !(MIN_LENGTH..=MAX_LENGTH).contains(&got) {
+        Err(ParseError::Unsupported(got))
+    } else if got != expected {
+        Err(ParseError::Length { expected, got })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::get_pattern;
+    use crate::filter::replay;
+    use crate::matrix::PatternMatrix;
+    use crate::words::{bundled_words, WordLists};
+
+    /// a game in `mode` where each guess got its pattern against `target`
+    fn game(mode: Mode, target: &str, guesses: &[&str]) -> GameState {
+        let mut state = GameState::with_mode(mode);
+        for guess in guesses {
+            state.push(guess.to_string(), get_pattern(guess, target));
+        }
+        state
+    }
+
+    #[test]
+    fn normal_mode_has_no_rules() {
+        let state = game(Mode::Normal, "there", &["crane"]);
+        assert_eq!(state.check("zzzzz"), Ok(()));
+    }
+
+    #[test]
+    fn green_must_stay_in_place() {
+        let state = game(Mode::Hard, "cloud", &["crane"]);
+        let err = Violation::Green {
+            letter: 'c',
+            position: 0,
+        };
+        assert_eq!(state.check("slate"), Err(err));
+        assert_eq!(state.check("clump"), Ok(()));
+    }
+
+    #[test]
+    fn revealed_letter_must_be_reused() {
+        let state = game(Mode::Hard, "shirt", &["crane"]);
+        let err = Violation::Missing {
+            letter: 'r',
+            count: 1,
+        };
+        assert_eq!(state.check("mould"), Err(err));
+        assert_eq!(state.check("brisk"), Ok(()));
+    }
+
+    #[test]
+    fn repeated_letter_counts_are_kept_across_rows() {
+        // eerie gets ybybg and speed gets bbgyb: two e's are known, and the
+        // second row doesn't add to the first
+        let state = game(Mode::Hard, "there", &["eerie", "speed"]);
+        let err = Violation::Missing {
+            letter: 'r',
+            count: 1,
+        };
+        assert_eq!(state.check("theme"), Err(err));
+        assert_eq!(state.check("there"), Ok(()));
+        assert_eq!(state.check("where"), Ok(()));
+    }
+
+    #[test]
+    fn black_repeat_still_needs_the_revealed_copies() {
+        let state = game(Mode::Hard, "there", &["eerie"]);
+        let err = Violation::Missing {
+            letter: 'e',
+            count: 2,
+        };
+        assert_eq!(state.check("throe"), Err(err));
+    }
+
+    #[test]
+    fn ultra_hard_yellow_cant_go_back() {
+        let state = game(Mode::UltraHard, "shirt", &["crane"]);
+        let err = Violation::Yellow {
+            letter: 'r',
+            position: 1,
+        };
+        assert_eq!(state.check("brisk"), Err(err));
+        assert_eq!(state.check("shirt"), Ok(()));
+        assert_eq!(game(Mode::Hard, "shirt", &["crane"]).check("brisk"), Ok(()));
+    }
+
+    #[test]
+    fn every_candidate_follows_the_rules() {
+        let answers: Vec<String> = bundled_words(u64::MAX)
+            .answers
+            .into_iter()
+            .step_by(7)
+            .collect();
+        let words = WordLists::single(answers);
+        let matrix = PatternMatrix::new(&words.guesses, &words.answers);
+        for target in words.answers.iter().step_by(31) {
+            for mode in [Mode::Hard, Mode::UltraHard] {
+                let state = game(mode, target, &["eerie", "crane", "spilt", "mummy"]);
+                let rules = state.rules();
+                for candidate in replay(&words, &matrix, &state) {
+                    let word = &words.answers[candidate];
+                    assert_eq!(rules.check(word), Ok(()), "{} for {}", word, target);
+                }
+            }
+        }
+    }
+    #[test]
+    fn game_state_round_trips_through_rows() {
+        let text = "crane bybbg\nspeed bbgyb";
+        let state: GameState = text.parse().unwrap();
+        assert_eq!(state.history.len(), 2);
+        assert_eq!(state.history[1].0, "speed");
+        assert_eq!(state.history[1].1, "bbgyb".parse().unwrap());
+        assert_eq!(state.to_string(), text);
+        assert_eq!("crane bybbg, speed bbgyb".parse(), Ok(state));
+    }
+
+    #[test]
+    fn game_state_rows_must_match_the_first() {
+        let err = "crane bybbg\nplanet bbbbbb".parse::<GameState>();
+        assert_eq!(
+            err,
+            Err(ParseError::Length {
+                expected: 5,
+                got: 6
+            })
+        );
+        let err = "crane bybbg bbbbb".parse::<GameState>();
+        assert_eq!(err, Err(ParseError::Row("crane bybbg bbbbb".to_string())));
+    }
+}
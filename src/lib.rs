@@ -0,0 +1,34 @@
+//! wordle solver library
+//!
+//! loads word lists, computes wordle feedback (precomputed for every word
+//! pair in a [`PatternMatrix`]), narrows candidate words from that feedback
+//! and solves games using a pluggable guess [`Strategy`]
+
+pub mod adversary;
+pub mod benchmark;
+pub mod boards;
+pub mod feedback;
+pub mod filter;
+pub mod format;
+pub mod game;
+pub mod matrix;
+pub mod prior;
+pub mod solver;
+pub mod strategy;
+pub mod words;
+
+pub use adversary::{solve_absurdle, worst_pattern, Absurdle};
+pub use benchmark::{benchmark, Report};
+pub use boards::{next_board_guess, solve_boards, Board};
+pub use feedback::{get_hints, get_pattern, is_winner, Feedback, Hint, ParseError, Pattern};
+pub use filter::{narrow_by_word, narrow_candidates, narrow_guesses, replay, Constraints};
+pub use format::{Entry, ListFormat};
+pub use game::{parse_row, parse_word, GameState, Mode, Rules, Violation};
+pub use matrix::PatternMatrix;
+pub use prior::Prior;
+pub use solver::{solve, Options, Outcome, SolveResult, Turn};
+pub use strategy::{Entropy, Frequency, Minimax, Strategy};
+pub use words::{
+    bundled_answers, bundled_guesses, bundled_words, cache_dir, default_words_path, download_words,
+    parse_entries, parse_words, read_words, WordLists,
+};
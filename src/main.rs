@@ -1,20 +1,26 @@
-extern crate reqwest;
-
-use clap::{AppSettings, Args, Parser, Subcommand};
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
-use std::path::Path;
+use clap::{AppSettings, ArgEnum, Args, Parser, Subcommand};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
+mod commands;
+mod tui;
 
-const FILENAME: &str = "./words.txt";
-
-/// stores hint information
-#[derive(Debug)]
-struct Hint {
-    letter: char,
-    position: usize,
-    kind: char,
-}
+use commands::{Command, DEFAULT_TOP, HELP};
+use tui::{App, Role};
+use wordle_solver::feedback::{DEFAULT_LENGTH, MAX_LENGTH, MIN_LENGTH};
+use wordle_solver::game::{parse_pattern, parse_played};
+use wordle_solver::solver::DEFAULT_MAX_TURNS;
+use wordle_solver::words::{cache_dir, DOWNLOAD_URL, FILENAME};
+use wordle_solver::{
+    benchmark, bundled_answers, bundled_guesses, download_words, get_hints, is_winner,
+    narrow_by_word, next_board_guess, parse_entries, parse_word, parse_words, replay, solve,
+    solve_absurdle, solve_boards, Absurdle, Board, Entropy, Feedback, Frequency, GameState, Hint,
+    ListFormat, Minimax, Mode, Options, Outcome, Pattern, PatternMatrix, Prior, Report,
+    SolveResult, Strategy, WordLists,
+};
 
 /// global args
 /// TODO make non positional
@@ -22,9 +28,142 @@ struct Hint {
 #[clap(name = "wordle")]
 #[clap(about = "wordle solver")]
 struct Struct {
-    /// number of words to source
+    /// number of answer words to source
     #[clap(short, long, default_value_t = 10000)]
     count: u64,
+
+    /// number of letters in each word, from 3 to 12
+    #[clap(short, long, default_value_t = DEFAULT_LENGTH, validator = valid_length)]
+    length: usize,
+
+    /// how the next guess is chosen
+    #[clap(short, long, arg_enum, default_value_t = StrategyKind::Frequency)]
+    strategy: StrategyKind,
+
+    /// frequency word list used for answers and guesses [default: words.txt
+    /// in the cache directory, if fetched]
+    #[clap(short, long, env = "WORDLE_WORDS")]
+    words: Option<PathBuf>,
+
+    /// directory for the fetched word list and pattern matrix caches
+    /// [default: ~/.cache/wordle]
+    #[clap(long, env = "WORDLE_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// prior probability of each answer: uniform, frequency (corpus
+    /// counts) or sigmoid[:center[:width]] over frequency rank
+    #[clap(long, default_value_t = Prior::Uniform)]
+    prior: Prior,
+
+    /// format of the word list files: plain, tsv, csv or json, optionally
+    /// gzip compressed [default: guessed from the file]
+    #[clap(long)]
+    list_format: Option<ListFormat>,
+
+    /// word list of possible answers, instead of words.txt
+    #[clap(long)]
+    answers: Option<PathBuf>,
+
+    /// word list of extra allowed guesses, which are never answers
+    #[clap(long)]
+    guesses: Option<PathBuf>,
+
+    /// hard mode: greens must stay in place and yellows must be reused
+    #[clap(long)]
+    hard: bool,
+
+    /// hard mode, and yellows can't go back where they were yellow
+    #[clap(long, conflicts_with = "hard")]
+    ultra_hard: bool,
+
+    /// print plain lines in play and host instead of the full-screen view
+    #[clap(long)]
+    plain: bool,
+
+    /// guesses allowed before the game is lost [default: 6, or 5 more than
+    /// the number of boards]
+    #[clap(long, validator = valid_max_turns)]
+    max_turns: Option<u32>,
+}
+
+impl Struct {
+    /// rules picked by the --hard, --ultra-hard and --max-turns flags for a
+    /// game on `boards` boards
+    fn options(&self, boards: usize) -> Options {
+        let mode = if self.ultra_hard {
+            Mode::UltraHard
+        } else if self.hard {
+            Mode::Hard
+        } else {
+            Mode::Normal
+        };
+        Options {
+            mode,
+            max_turns: self
+                .max_turns
+                .unwrap_or(DEFAULT_MAX_TURNS + boards as u32 - 1),
+        }
+    }
+}
+
+/// checks a --length value is a supported word length
+fn valid_length(s: &str) -> Result<(), String> {
+    match s.parse::<usize>() {
+        Ok(n) if (MIN_LENGTH..=MAX_LENGTH).contains(&n) => Ok(()),
+        _ => Err(format!(
+            "word length must be a number from {} to {}",
+            MIN_LENGTH, MAX_LENGTH
+        )),
+    }
+}
+
+/// checks a --max-turns value allows at least one guess
+fn valid_max_turns(s: &str) -> Result<(), String> {
+    match s.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err("max turns must be a positive number".to_string()),
+    }
+}
+
+/// checks a --boards value is at least one board
+fn valid_boards(s: &str) -> Result<(), String> {
+    match s.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err("boards must be a positive number".to_string()),
+    }
+}
+
+/// guess selection strategies
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum StrategyKind {
+    /// most frequent remaining word
+    Frequency,
+    /// word whose feedback splits the remaining words most evenly
+    Entropy,
+    /// word whose largest group of remaining words is smallest
+    Minimax,
+}
+
+impl StrategyKind {
+    /// builds the strategy this kind selects
+    fn strategy(self) -> Box<dyn Strategy + Sync> {
+        match self {
+            StrategyKind::Frequency => Box::new(Frequency),
+            StrategyKind::Entropy => Box::new(Entropy),
+            StrategyKind::Minimax => Box::new(Minimax),
+        }
+    }
+}
+
+/// benchmark report formats
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    /// readable summary with a turn histogram
+    Human,
+    /// single JSON object
+    Json,
+    /// metric,value rows
+    Csv,
 }
 
 /// CLI struct
@@ -45,277 +184,685 @@ enum Commands {
     /// try and solve the target word in fewest number of turns
     #[clap(setting(AppSettings::ArgRequiredElseHelp))]
     Solve {
-        /// target word to solve for
-        #[clap()]
-        target: String,
+        /// target word to solve for, or several to solve them at once on
+        /// separate boards like dordle and quordle
+        #[clap(required = true)]
+        targets: Vec<String>,
     },
 
     /// interactively play wordle
     #[clap()]
-    Play {},
+    Play {
+        /// number of words played at once: 2 for dordle, 4 for quordle, 8
+        /// for octordle
+        #[clap(short, long, default_value_t = 1, validator = valid_boards)]
+        boards: usize,
+    },
+
+    /// download a frequency ordered word list to words.txt
+    #[clap()]
+    Fetch {
+        /// where to download the word list from
+        #[clap(long, env = "WORDLE_URL", default_value = DOWNLOAD_URL)]
+        url: String,
+    },
 
     /// benchmark system speed
     #[clap()]
-    Benchmark {},
+    Benchmark {
+        /// number of threads to solve with, 0 for one per core
+        #[clap(short, long, default_value_t = 0)]
+        threads: usize,
+
+        /// how to print the report
+        #[clap(short, long, arg_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+
+        /// play one game against an absurdle host instead of solving every
+        /// answer
+        #[clap(long)]
+        adversary: bool,
+    },
+
+    /// guess against a host that dodges your guesses for as long as it can
+    #[clap()]
+    Absurdle {},
+
+    /// guess a secret word the computer picks from the answers
+    #[clap()]
+    Host {
+        /// seed for picking the word, to replay a game [default: random]
+        #[clap(long)]
+        seed: Option<u64>,
+    },
 }
 
 #[tokio::main]
 async fn main() {
-    if !Path::new(FILENAME).exists() {
-        println!("words.txt not found, downloading...");
-        let res = download_words().await;
-        match res {
+    let args = Cli::parse();
+
+    let cache_dir = args.delegate.cache_dir.clone().unwrap_or_else(cache_dir);
+    let words_path = match &args.delegate.words {
+        Some(path) => path.clone(),
+        None => cache_dir.join(FILENAME),
+    };
+
+    if let Commands::Fetch { url } = &args.command {
+        println!("downloading {} to {}", url, words_path.display());
+        match download_words(url, &words_path).await {
             Ok(v) => println!("done: {:?}", v),
-            Err(e) => {
-                println!("error: {:?}", e);
-                return;
-            }
+            Err(e) => println!("error: {}", e),
         }
+        return;
     }
 
-    let args = Cli::parse();
-
-    println!("parsing words c={:?}", args.delegate.count);
-    let mut words: Vec<String> = Vec::new();
-    let res = parse_words(&mut words, args.delegate.count);
-    match res {
-        Ok(v) => println!("done: {:?}", v),
+    eprintln!(
+        "parsing words c={:?} l={:?}",
+        args.delegate.count, args.delegate.length
+    );
+    let res = load_words(&args.delegate, &words_path);
+    let words = match res {
+        Ok(v) => {
+            eprintln!(
+                "done: {:?} answers, {:?} guesses",
+                v.answers.len(),
+                v.guesses.len()
+            );
+            v
+        }
         Err(e) => {
-            println!("error: {:?}", e);
+            eprintln!("error: {}", e);
             return;
         }
-    }
+    };
 
+    eprintln!("loading pattern matrix");
+    let start = Instant::now();
+    let matrix = match PatternMatrix::cached(&words.guesses, &words.answers, &cache_dir) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("error: {:?}, building without cache", e);
+            PatternMatrix::new(&words.guesses, &words.answers)
+        }
+    };
+    eprintln!("done: took {:.2?}", start.elapsed());
+
+    let strategy = args.delegate.strategy.strategy();
+    let length = args.delegate.length;
+    let boards = match &args.command {
+        Commands::Solve { targets } => targets.len(),
+        Commands::Play { boards } => *boards,
+        _ => 1,
+    };
+    if boards > 1 && (args.delegate.hard || args.delegate.ultra_hard) {
+        println!("hard mode only works with a single board");
+        return;
+    }
+    let options = args.delegate.options(boards);
     match &args.command {
-        Commands::Solve { target } => {
-            if target.len() != 5 {
-                println!("target must be 5 characters in length");
+        Commands::Solve { targets } => {
+            if let Some(e) = targets.iter().find_map(|t| parse_word(t, length).err()) {
+                println!("invalid target: {}", e);
                 return;
             }
-            println!("attempting to solve with target {:?}", target);
             let start = Instant::now();
-            solve(words, target.to_string(), false);
+            if let [target] = &targets[..] {
+                println!("attempting to solve with target {:?}", target);
+                let result = solve(&words, &matrix, target, strategy.as_ref(), &options);
+                print_solve(&result, &options);
+            } else {
+                println!("attempting to solve with targets {:?}", targets);
+                let results = solve_boards(&words, &matrix, targets, strategy.as_ref(), &options);
+                print_boards(&results);
+                for (i, result) in results.iter().enumerate() {
+                    println!(
+                        "board {}: {:?} {:?} after {} turns",
+                        i + 1,
+                        result.target,
+                        result.outcome,
+                        result.turn_count()
+                    );
+                }
+            }
             let end = start.elapsed();
             println!("took {:.2?}", end);
         }
-        Commands::Play {} => {
-            println!("playing wordle");
-            play(words)
+        Commands::Play { boards: 1 } => {
+            println!("playing wordle ({} mode)", options.mode);
+            if full_screen(&args.delegate) {
+                let app = App::new(
+                    &words,
+                    &matrix,
+                    strategy.as_ref(),
+                    &options,
+                    length,
+                    Role::Assistant,
+                );
+                if let Err(e) = app.run() {
+                    println!("error: {}", e);
+                }
+            } else {
+                play(&words, &matrix, strategy.as_ref(), &options, length)
+            }
+        }
+        Commands::Play { boards } => {
+            println!("playing {} boards", boards);
+            play_boards(
+                &words,
+                &matrix,
+                strategy.as_ref(),
+                &options,
+                *boards,
+                length,
+            )
+        }
+        Commands::Fetch { .. } => unreachable!("handled before loading words"),
+        Commands::Benchmark {
+            threads,
+            format,
+            adversary,
+        } => {
+            eprintln!("benchmarking");
+            run_benchmark(
+                &words,
+                &matrix,
+                strategy.as_ref(),
+                &options,
+                *threads,
+                *format,
+                *adversary,
+            );
         }
-        Commands::Benchmark {} => {
-            println!("benchmarking");
-            benchmark(words);
+        Commands::Absurdle {} => {
+            println!("playing absurdle ({} mode)", options.mode);
+            absurdle(&words, &matrix, &options, length)
+        }
+        Commands::Host { seed } => {
+            let seed = seed.unwrap_or_else(rand::random);
+            println!("hosting wordle ({} mode, seed {})", options.mode, seed);
+            let target = pick_answer(&words, seed);
+            if full_screen(&args.delegate) {
+                let role = Role::Host { target };
+                let app = App::new(&words, &matrix, strategy.as_ref(), &options, length, role);
+                if let Err(e) = app.run() {
+                    println!("error: {}", e);
+                }
+            } else {
+                host(&words, &options, target, length)
+            }
         }
     }
 }
 
-/// downloads a list of words ordered by how frequently they are used
-async fn download_words() -> io::Result<()> {
-    let resp = reqwest::get("https://norvig.com/ngrams/count_1w.txt")
-        .await
-        .expect("request failed");
-    let body = resp.text().await.expect("body invalid");
-    let mut out = File::create(FILENAME).expect("failed to create file");
-    io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-    Ok(())
-}
-
-/// reads a word file and parses it into a vector
-fn parse_words(words: &mut Vec<String>, count: u64) -> io::Result<()> {
-    let file = File::open(FILENAME)?;
-    let reader = BufReader::new(file);
-
-    let mut c = count; // number of top words to grab for initial guess
-    for line in reader.lines() {
-        match line {
-            Ok(l) => {
-                let mut split: Vec<&str> = l.split('\t').collect();
-                split.pop(); // remove freq we dont need
-                let word = split.pop().unwrap(); // get actual word
-                if word.chars().count() != 5 {
-                    continue;
-                }
-                words.push(word.to_string());
-                c = c - 1;
-                if c <= 0 {
-                    break;
-                }
-            }
-            Err(e) => return Err(e),
+/// loads the answer list and allowed guess list, falling back from the
+/// given paths to the words file and then to the bundled lists
+fn load_words(args: &Struct, words_path: &Path) -> io::Result<WordLists> {
+    let (answers, mut allowed) = match &args.answers {
+        Some(path) => (
+            parse_entries(path, args.list_format, args.length, args.count)?,
+            Vec::new(),
+        ),
+        // an explicitly chosen words file must exist, so let the open fail
+        None if args.words.is_some() || words_path.exists() => (
+            parse_entries(words_path, args.list_format, args.length, args.count)?,
+            Vec::new(),
+        ),
+        None if args.length != DEFAULT_LENGTH => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "{} not found, and the bundled word list only has {} letter words",
+                    words_path.display(),
+                    DEFAULT_LENGTH
+                ),
+            ))
         }
+        None => {
+            eprintln!(
+                "{} not found, using bundled word list (see `wordle fetch`)",
+                words_path.display()
+            );
+            (bundled_answers(args.count), bundled_guesses())
+        }
+    };
+    if let Some(path) = &args.guesses {
+        allowed.extend(parse_words(path, args.list_format, args.length, u64::MAX)?);
     }
 
-    Ok(())
+    let counts: Vec<Option<u64>> = answers.iter().map(|entry| entry.count).collect();
+    let answers: Vec<String> = answers.into_iter().map(|entry| entry.word).collect();
+    if answers.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("no {} letter answers found", args.length),
+        ));
+    }
+    args.prior
+        .check(&counts)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut words = WordLists::new(answers, allowed);
+    words.set_prior(args.prior, &counts);
+    Ok(words)
 }
 
-/// solves a wordle until it finds the word or gives up
-fn solve(words: Vec<String>, target: String, quiet: bool) -> u32 {
-    let mut turn = 0u32;
-    let mut possible_words = words.clone();
+/// interactively plays wordle with the user
+fn play(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    strategy: &dyn Strategy,
+    options: &Options,
+    length: usize,
+) {
+    let mut state = GameState::with_mode(options.mode);
+    let mut candidates: Vec<usize> = (0..words.answers.len()).collect();
+    // suggestion for the current turn, worked out again after any change
+    let mut suggestion = None;
+    println!("enter hints as string where green='g', yellow='y', and black='b' (example: ggybb)");
+    println!("if you played a different word, enter it before the hints (example: crane bygbb)");
+    println!("{}", HELP);
     loop {
-        turn += 1;
-        if !quiet {
-            println!("turn: {:?}", turn);
+        let out_of_turns = state.turn() > options.max_turns;
+        if suggestion.is_none() && !candidates.is_empty() && !out_of_turns {
+            println!("turn: {:?}", state.turn());
+            let guess = strategy.next_guess(&state, words, matrix, &candidates);
+            println!("try: {:?}", words.guesses[guess]);
+            suggestion = Some(guess);
+        }
+        let mut hint = String::new();
+        match suggestion {
+            Some(_) => println!("enter hint string:"),
+            None => println!("enter a command:"),
         }
-        let most_popular = possible_words.get(0).unwrap().to_string();
-        if !quiet {
-            println!("guess: {:?}", most_popular);
+        if std::io::stdin().read_line(&mut hint).unwrap() == 0 {
+            return;
         }
-        let hints = get_hints(&most_popular, &target);
-        if is_winner(&hints) {
-            if !quiet {
-                println!("word: {:?}, turn: {:?}", most_popular, turn);
+
+        if hint.trim_start().starts_with(':') {
+            let command = match hint.parse() {
+                Ok(c) => c,
+                Err(e) => {
+                    println!("{}", e);
+                    continue;
+                }
+            };
+            match command {
+                Command::Undo => match state.undo() {
+                    Some((word, pattern)) => println!("undid {} {}", word, pattern),
+                    None => println!("nothing to undo"),
+                },
+                Command::Reset => {
+                    state.reset();
+                    println!("starting over");
+                }
+                Command::History if state.history.is_empty() => println!("no guesses yet"),
+                Command::History => {
+                    for (i, (word, pattern)) in state.history.iter().enumerate() {
+                        println!("  {}: {} {}", i + 1, word, pattern);
+                    }
+                }
+                Command::Top(n) => print_likely(words, &candidates, n),
+                Command::Candidates => {
+                    let all: Vec<&str> = candidates
+                        .iter()
+                        .map(|&c| words.answers[c].as_str())
+                        .collect();
+                    println!("possible words ({}): {}", all.len(), all.join(" "));
+                }
+                Command::Help => println!("{}", HELP),
+            }
+            if matches!(command, Command::Undo | Command::Reset) {
+                candidates = replay(words, matrix, &state);
+                suggestion = None;
+                println!("possible words: {:?}", candidates.len());
             }
-            return turn;
+            continue;
         }
-        if turn >= 6 {
-            if !quiet {
-                println!("could not find word after 6 turns");
+
+        let guess = match suggestion {
+            Some(g) => g,
+            None if out_of_turns => {
+                println!("out of turns, :undo or :reset to continue");
+                continue;
+            }
+            None => {
+                println!("no words left, :undo or :reset to continue");
+                continue;
             }
-            return 7;
+        };
+        let (word, pattern) = match parse_played(&hint, &words.guesses[guess], length) {
+            Ok(played) => played,
+            Err(e) => {
+                println!("invalid hint string: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = state.check(&word) {
+            println!("not allowed in {} mode: {}", options.mode, e);
+            continue;
+        }
+        if pattern.is_win() {
+            println!("we did it!");
+            break;
         }
-        possible_words = narrow_guesses(possible_words, hints);
-        if !quiet {
-            println!("possible words: {:?}", possible_words.len());
+        candidates = narrow_by_word(words, matrix, &candidates, &word, pattern);
+        state.push(word, pattern);
+        suggestion = None;
+        println!("possible words: {:?}", candidates.len());
+        // kept going, so a mistyped last hint can still be undone
+        if state.turn() > options.max_turns {
+            println!(
+                "out of turns after {}, :undo a mistyped hint",
+                options.max_turns
+            );
+            continue;
         }
-        if possible_words.len() <= 0 {
-            if !quiet {
-                println!("word not found, try sourcing more words with --count arg (see --help)");
-            }
-            return 7;
+        if candidates.is_empty() {
+            println!("word not found, :undo a mistyped hint or try sourcing more words with --count arg (see --help)");
+            continue;
         }
+        print_likely(words, &candidates, DEFAULT_TOP);
     }
 }
 
-/// interactively plays wordle with the user
-fn play(words: Vec<String>) {
-    let mut turn = 0u32;
-    let mut possible_words = words.clone();
+/// interactively plays several boards at once, asking for each unfinished
+/// board's hint after every guess
+fn play_boards(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    strategy: &dyn Strategy,
+    options: &Options,
+    boards: usize,
+    length: usize,
+) {
+    let mut boards: Vec<Board> = (0..boards)
+        .map(|_| Board::new(words, options.mode))
+        .collect();
     println!("enter hints as string where green='g', yellow='y', and black='b' (example: ggybb)");
+    for turn in 1..=options.max_turns {
+        println!("turn: {:?}", turn);
+        let guess = next_board_guess(words, matrix, strategy, &boards);
+        println!("try: {:?}", words.guesses[guess]);
+        for (i, board) in boards.iter_mut().enumerate() {
+            if board.is_done() {
+                continue;
+            }
+            let pattern = loop {
+                let mut hint = String::new();
+                println!("enter hint string for board {}:", i + 1);
+                if std::io::stdin().read_line(&mut hint).unwrap() == 0 {
+                    return;
+                }
+                match parse_pattern(&hint, length) {
+                    Ok(p) => break p,
+                    Err(e) => println!("invalid hint string: {}", e),
+                }
+            };
+            board.play(words, matrix, guess, pattern);
+            if pattern.is_win() {
+                println!("board {} solved!", i + 1);
+            } else if board.candidates.is_empty() {
+                println!("board {}: word not found, try sourcing more words with --count arg (see --help)", i + 1);
+            } else {
+                println!(
+                    "board {} possible words: {:?}",
+                    i + 1,
+                    board.candidates.len()
+                );
+            }
+        }
+        if boards.iter().all(Board::is_done) {
+            let solved = boards.iter().filter(|b| b.state.is_won()).count();
+            println!("solved {} of {} boards", solved, boards.len());
+            return;
+        }
+    }
+    println!("out of turns after {}", options.max_turns);
+}
+
+/// hosts an absurdle game for the user to guess against
+fn absurdle(words: &WordLists, matrix: &PatternMatrix, options: &Options, length: usize) {
+    let mut host = Absurdle::new(words, GameState::with_mode(options.mode));
+    println!("enter guesses, the word changes to avoid them for as long as it can");
     loop {
-        turn += 1;
+        let turn = host.state.turn();
         println!("turn: {:?}", turn);
-        let guess = possible_words.get(0).unwrap().to_string();
-        println!("try: {:?}", guess);
-        let mut hint = String::new();
-        println!("enter hint string:");
-        std::io::stdin().read_line(&mut hint).unwrap();
-        hint.pop();
-        if hint.len() != 5 {
-            println!("invalid hint string");
-            turn -= 1;
+        let mut line = String::new();
+        println!("enter guess:");
+        if std::io::stdin().read_line(&mut line).unwrap() == 0 {
+            return;
+        }
+        let word = match parse_word(&line.to_ascii_lowercase(), length) {
+            Ok(w) => w,
+            Err(e) => {
+                println!("invalid guess: {}", e);
+                continue;
+            }
+        };
+        let guess = match words.guess_index(&word) {
+            Some(g) => g,
+            None => {
+                println!("not in word list: {}", word);
+                continue;
+            }
+        };
+        if let Err(e) = host.state.check(&word) {
+            println!("not allowed in {} mode: {}", options.mode, e);
             continue;
         }
-        if hint == "ggggg" {
-            println!("we did it!");
-            break;
+        let pattern = host.guess(words, matrix, guess);
+        print_hints(&pattern.hints(&word));
+        if pattern.is_win() {
+            println!("solved in {} turns!", turn);
+            return;
         }
-        let mut hints: Vec<Hint> = Vec::new();
-        let mut pos = 0;
-        for h in hint.chars() {
-            hints.push(Hint {
-                kind: h,
-                position: pos,
-                letter: guess.chars().nth(pos).unwrap(),
-            });
-            pos += 1;
-        }
-        possible_words = narrow_guesses(possible_words, hints);
-        println!("possible words: {:?}", possible_words.len());
-        if possible_words.len() <= 0 {
-            println!("word not found, try sourcing more words with --count arg (see --help)");
+        println!("possible words: {:?}", host.candidates.len());
+        if turn >= options.max_turns {
+            println!(
+                "out of turns, the word could have been {}",
+                words.answers[host.candidates[0]]
+            );
             return;
         }
     }
 }
 
-/// solves all words in set and computes stats
-fn benchmark(words: Vec<String>) {
-    let possible_words = words.clone();
-    let mut average_turn_sum = 0;
-    let mut unsolved = 0;
-    let start = Instant::now();
-    for word in possible_words {
-        let turn = solve(words.clone(), word, true);
-        if turn == 7 {
-            unsolved += 1;
-            continue
+/// answer picked at random with `seed`, using a generator whose output is
+/// fixed across versions so a seed always replays the same game
+fn pick_answer(words: &WordLists, seed: u64) -> &str {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    &words.answers[rng.gen_range(0..words.answers.len())]
+}
+
+/// determines if play and host should take over the terminal, which needs
+/// a terminal on both ends and no --plain
+fn full_screen(args: &Struct) -> bool {
+    !args.plain && io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// hosts a game with a secret answer, scoring the user's guesses against it
+fn host(words: &WordLists, options: &Options, target: &str, length: usize) {
+    let allowed: HashSet<&str> = words.guesses.iter().map(String::as_str).collect();
+    let mut state = GameState::with_mode(options.mode);
+    println!("guess the {} letter word", length);
+    while state.turn() <= options.max_turns {
+        println!("turn: {:?}", state.turn());
+        let mut line = String::new();
+        println!("enter guess:");
+        if std::io::stdin().read_line(&mut line).unwrap() == 0 {
+            return;
         }
-        average_turn_sum += turn
+        let word = match parse_word(&line.to_ascii_lowercase(), length) {
+            Ok(w) => w,
+            Err(e) => {
+                println!("invalid guess: {}", e);
+                continue;
+            }
+        };
+        if !allowed.contains(word.as_str()) {
+            println!("not in word list: {}", word);
+            continue;
+        }
+        if let Err(e) = state.check(&word) {
+            println!("not allowed in {} mode: {}", options.mode, e);
+            continue;
+        }
+        let hints = get_hints(&word, target);
+        print_hints(&hints);
+        if is_winner(&hints) {
+            println!("solved in {} turns!", state.turn());
+            return;
+        }
+        let kinds: Vec<Feedback> = hints.iter().map(|h| h.kind).collect();
+        state.push(word, Pattern::new(&kinds));
     }
-    let end = start.elapsed();
+    println!("out of turns, the word was {}", target);
+}
 
-    let average_turn: f32 = average_turn_sum as f32 / (words.len() as f32);
+/// prints a guess's hints as coloured tiles, or as the word and its pattern
+/// when stdout isn't a terminal
+fn print_hints(hints: &[Hint]) {
+    let word: String = hints.iter().map(|h| h.letter).collect();
+    if !io::stdout().is_terminal() {
+        let pattern: String = hints.iter().map(|h| h.kind.to_char()).collect();
+        println!("{} {}", word, pattern);
+        return;
+    }
+    let tiles: String = hints
+        .iter()
+        .map(|h| {
+            let colour = match h.kind {
+                Feedback::Green => "42",
+                Feedback::Yellow => "43",
+                Feedback::Black => "100",
+            };
+            format!(
+                "\x1b[1;97;{}m {} \x1b[0m",
+                colour,
+                h.letter.to_ascii_uppercase()
+            )
+        })
+        .collect();
+    println!("{}", tiles);
+}
 
-    println!("average solve turn: {:?}", average_turn);
-    println!("unable to solve: {:?}", unsolved);
-    println!("took {:.2?}", end);
+/// prints the `top` most likely remaining candidates with their
+/// probabilities
+fn print_likely(words: &WordLists, candidates: &[usize], top: usize) {
+    let mut likely: Vec<(usize, f64)> = candidates
+        .iter()
+        .copied()
+        .zip(words.probabilities(candidates))
+        .collect();
+    likely.sort_by(|a, b| b.1.total_cmp(&a.1));
+    for (answer, p) in likely.iter().take(top) {
+        println!("  {} {:.1}%", words.answers[*answer], p * 100.0);
+    }
+    if likely.len() > top {
+        println!("  ... and {} more", likely.len() - top);
+    }
 }
 
-/// narrows down potential guesses based on provided hints
-fn narrow_guesses(words: Vec<String>, hints: Vec<Hint>) -> Vec<String> {
-    let mut guesses: Vec<String> = Vec::new();
-    for word in words {
-        let mut is_valid = true;
-        for hint in &hints {
-            if hint.kind == 'g' && word.chars().nth(hint.position).unwrap() != hint.letter {
-                is_valid = false;
-                break;
-            }
-            if hint.kind == 'y'
-                && (word.chars().nth(hint.position).unwrap() == hint.letter
-                    || !word.contains(hint.letter))
-            {
-                is_valid = false;
-                break;
-            }
-            if hint.kind == 'b' && word.contains(hint.letter) {
-                is_valid = false;
-                break;
-            }
+/// prints each turn of a solve and how it ended
+fn print_solve(result: &SolveResult, options: &Options) {
+    for (i, turn) in result.turns.iter().enumerate() {
+        println!("turn: {:?}", i + 1);
+        println!("guess: {:?}", turn.guess);
+        if !turn.pattern.is_win() {
+            println!("possible words: {:?}", turn.remaining);
         }
-        if is_valid {
-            guesses.push(word)
+    }
+    match result.outcome {
+        Outcome::Solved => println!("word: {:?}, turn: {:?}", result.target, result.turn_count()),
+        Outcome::NoCandidates => {
+            println!("word not found, try sourcing more words with --count arg (see --help)")
         }
+        Outcome::OutOfTurns => println!("could not find word after {} turns", options.max_turns),
     }
-    return guesses;
 }
 
-/// gets a list of hints for the provided guess against the target word
-fn get_hints(guess: &String, target: &String) -> Vec<Hint> {
-    let mut pos: usize = 0;
-    let mut hints: Vec<Hint> = Vec::new();
-    for c in guess.chars() {
-        let mut hint = 'b';
-
-        if target.contains(c) {
-            if target.chars().nth(pos).unwrap() == c {
-                hint = 'g'
-            } else {
-                hint = 'y'
-            }
+/// prints each shared guess of a multi-board solve and what every board
+/// still in play showed for it
+fn print_boards(results: &[SolveResult]) {
+    let turns = results.iter().map(|r| r.turns.len()).max().unwrap_or(0);
+    for turn in 0..turns {
+        let mut played = results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.turns.get(turn).map(|t| (i, t)))
+            .peekable();
+        if let Some((_, first)) = played.peek() {
+            println!("turn: {:?}, guess: {:?}", turn + 1, first.guess);
+        }
+        for (i, t) in played {
+            println!(
+                "  board {}: {}, possible words: {:?}",
+                i + 1,
+                t.pattern,
+                t.remaining
+            );
         }
+    }
+}
 
-        if !target.contains(c) {
-            hint = 'b'
+/// solves all words in set, or a single game against an absurdle host, and
+/// prints stats in the requested format
+fn run_benchmark(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    strategy: &(dyn Strategy + Sync),
+    options: &Options,
+    threads: usize,
+    format: OutputFormat,
+    adversary: bool,
+) {
+    let start = Instant::now();
+    let results = if adversary {
+        let result = solve_absurdle(words, matrix, strategy, options);
+        if let OutputFormat::Human = format {
+            for (i, turn) in result.turns.iter().enumerate() {
+                println!(
+                    "turn: {:?}, guess: {:?}, pattern: {}, possible words: {:?}",
+                    i + 1,
+                    turn.guess,
+                    turn.pattern,
+                    turn.remaining
+                );
+            }
         }
+        vec![result]
+    } else {
+        benchmark(words, matrix, strategy, options, threads)
+    };
+    let report = Report::new(&results, options, start.elapsed());
 
-        hints.push(Hint {
-            kind: hint,
-            letter: c,
-            position: pos,
-        });
-        pos = pos + 1;
+    match format {
+        OutputFormat::Human => println!("{}", report),
+        OutputFormat::Json => println!("{}", report.to_json()),
+        OutputFormat::Csv => println!("{}", report.to_csv()),
     }
-    return hints;
 }
 
-/// determines if all hints are green
-fn is_winner(hints: &Vec<Hint>) -> bool {
-    for hint in hints {
-        if hint.kind != 'g' {
-            return false;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str], boards: usize) -> Options {
+        let args = Cli::try_parse_from(args).unwrap();
+        args.delegate.options(boards)
+    }
+
+    #[test]
+    fn boards_get_five_extra_turns_by_default() {
+        assert_eq!(options(&["wordle", "play"], 1).max_turns, 6);
+        assert_eq!(
+            options(&["wordle", "play", "--boards", "4"], 4).max_turns,
+            9
+        );
+        assert_eq!(
+            options(&["wordle", "play", "--boards", "8"], 8).max_turns,
+            13
+        );
+        let args = ["wordle", "--max-turns", "7", "play", "--boards", "4"];
+        assert_eq!(options(&args, 4).max_turns, 7);
     }
-    return true;
 }
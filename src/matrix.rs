@@ -0,0 +1,450 @@
+use memmap2::Mmap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, prelude::*, BufWriter};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use crate::feedback::{Pattern, MAX_LENGTH};
+
+/// version of the feedback rules, bump whenever `pattern_code` changes so
+/// cached matrices built with the old rules are ignored. `get_pattern` scores
+/// with `pattern_code` too, so this is the only copy of the rules
+const RULES_VERSION: u64 = 1;
+
+/// magic bytes at the start of a cached matrix file
+const MAGIC: &[u8; 8] = b"WRDLPM01";
+
+/// size of the cache file header: magic, key, guess count, answer count
+const HEADER_LEN: usize = 32;
+
+/// how long a temp file can go untouched before it is taken to be left over
+/// from an interrupted save rather than one still being written
+const STALE_TMP: Duration = Duration::from_secs(10 * 60);
+
+/// encodes a word as one byte per letter, if it is made of exactly `length`
+/// lowercase ascii letters
+pub fn encode(word: &str, length: usize) -> Option<&[u8]> {
+    let bytes = word.as_bytes();
+    if bytes.len() == length && bytes.iter().all(u8::is_ascii_lowercase) {
+        Some(bytes)
+    } else {
+        None
+    }
+}
+
+/// computes the pattern code for encoded words of the same length
+///
+/// greens are assigned first, then yellows are handed out left to right
+/// only while the answer still has unmatched copies of that letter
+pub fn pattern_code(guess: &[u8], answer: &[u8]) -> u32 {
+    let length = guess.len();
+    let mut digits = [0u32; MAX_LENGTH];
+    // letters in the answer not already matched by a green
+    let mut remaining = [0u8; 26];
+    for pos in 0..length {
+        if guess[pos] == answer[pos] {
+            digits[pos] = 2;
+        } else {
+            remaining[(answer[pos] - b'a') as usize] += 1;
+        }
+    }
+    for pos in 0..length {
+        let left = &mut remaining[(guess[pos] - b'a') as usize];
+        if digits[pos] == 0 && *left > 0 {
+            *left -= 1;
+            digits[pos] = 1;
+        }
+    }
+    digits[..length]
+        .iter()
+        .rev()
+        .fold(0, |code, d| code * 3 + d)
+}
+
+/// bytes needed to store any pattern code for words of `length` letters
+fn code_width(length: usize) -> usize {
+    match Pattern::count(length) {
+        n if n <= 1 << 8 => 1,
+        n if n <= 1 << 16 => 2,
+        _ => 4,
+    }
+}
+
+/// feedback pattern code of every guess against every answer, stored one
+/// row per guess so scoring a guess walks contiguous memory
+///
+/// codes take one, two or four little-endian bytes each depending on how
+/// many patterns the word length allows
+pub struct PatternMatrix {
+    guesses: usize,
+    answers: usize,
+    length: usize,
+    width: usize,
+    codes: Codes,
+}
+
+/// backing storage for the pattern codes
+enum Codes {
+    Owned(Vec<u8>),
+    /// a cache file mapped into memory, with the codes after the header
+    Mapped(Mmap),
+}
+
+impl Deref for Codes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Codes::Owned(codes) => codes,
+            Codes::Mapped(map) => &map[HEADER_LEN..],
+        }
+    }
+}
+
+/// pattern codes of one guess against every answer
+#[derive(Clone, Copy)]
+pub struct Row<'a> {
+    codes: &'a [u8],
+    width: usize,
+}
+
+impl Row<'_> {
+    /// pattern code the guess gets against the answer
+    #[inline]
+    pub fn get(&self, answer: usize) -> u32 {
+        let at = answer * self.width;
+        match self.width {
+            1 => self.codes[at] as u32,
+            2 => u16::from_le_bytes([self.codes[at], self.codes[at + 1]]) as u32,
+            _ => u32::from_le_bytes(self.codes[at..at + 4].try_into().unwrap()),
+        }
+    }
+}
+
+impl PatternMatrix {
+    /// computes the pattern of every guess against every answer
+    ///
+    /// panics if the words aren't all made of the same number of lowercase
+    /// letters
+    pub fn new(guesses: &[String], answers: &[String]) -> PatternMatrix {
+        let length = word_length(guesses, answers);
+        let width = code_width(length);
+        let guesses: Vec<&[u8]> = guesses.iter().map(|w| encode_or_panic(w, length)).collect();
+        let answers: Vec<&[u8]> = answers.iter().map(|w| encode_or_panic(w, length)).collect();
+
+        let mut codes = Vec::with_capacity(guesses.len() * answers.len() * width);
+        for guess in &guesses {
+            for answer in &answers {
+                let code = pattern_code(guess, answer);
+                codes.extend_from_slice(&code.to_le_bytes()[..width]);
+            }
+        }
+        PatternMatrix {
+            guesses: guesses.len(),
+            answers: answers.len(),
+            length,
+            width,
+            codes: Codes::Owned(codes),
+        }
+    }
+
+    /// loads the matrix for these words from a cache file in `dir`, building
+    /// and saving it first if there is no cache for this exact word list
+    pub fn cached(
+        guesses: &[String],
+        answers: &[String],
+        dir: impl AsRef<Path>,
+    ) -> io::Result<PatternMatrix> {
+        fs::create_dir_all(&dir)?;
+        let length = word_length(guesses, answers);
+        let key = cache_key(guesses, answers);
+        let path = cache_path(&dir, key);
+        let (g, a) = (guesses.len(), answers.len());
+        if let Some(matrix) = PatternMatrix::load(&path, key, g, a, length)? {
+            return Ok(matrix);
+        }
+
+        let matrix = PatternMatrix::new(guesses, answers);
+        matrix.save(&path, key)?;
+        remove_stale(dir.as_ref(), &path);
+        // map the file we just wrote so the built copy can be freed
+        let loaded = PatternMatrix::load(&path, key, g, a, length)?;
+        Ok(loaded.unwrap_or(matrix))
+    }
+
+    /// memory-maps a cache file, returning None if it is missing or was
+    /// written for a different word list, rules or size
+    fn load(
+        path: &Path,
+        key: u64,
+        guesses: usize,
+        answers: usize,
+        length: usize,
+    ) -> io::Result<Option<PatternMatrix>> {
+        let file = match File::open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        // safety: cache files are only ever replaced by renaming a complete
+        // file over them, never modified in place
+        let map = unsafe { Mmap::map(&file)? };
+        let header = header(key, guesses, answers);
+        let width = code_width(length);
+        if map.len() != HEADER_LEN + guesses * answers * width || map[..HEADER_LEN] != header {
+            return Ok(None);
+        }
+        Ok(Some(PatternMatrix {
+            guesses,
+            answers,
+            length,
+            width,
+            codes: Codes::Mapped(map),
+        }))
+    }
+
+    /// writes the matrix to a cache file, replacing any existing one
+    fn save(&self, path: &Path, key: u64) -> io::Result<()> {
+        // unique per writer, so processes building the same matrix at once
+        // never rename a file another one is still writing
+        let suffix = format!("{}-{:08x}.tmp", process::id(), rand::random::<u32>());
+        let tmp = path.with_extension(suffix);
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)
+            .and_then(|file| {
+                let mut out = BufWriter::new(file);
+                out.write_all(&header(key, self.guesses, self.answers))?;
+                out.write_all(&self.codes)?;
+                out.flush()
+            });
+        match written.and_then(|()| fs::rename(&tmp, path)) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let _ = fs::remove_file(&tmp);
+                Err(e)
+            }
+        }
+    }
+
+    /// number of guesses (rows)
+    pub fn guess_count(&self) -> usize {
+        self.guesses
+    }
+
+    /// number of answers (columns)
+    pub fn answer_count(&self) -> usize {
+        self.answers
+    }
+
+    /// number of letters in every word
+    pub fn word_length(&self) -> usize {
+        self.length
+    }
+
+    /// pattern the guess gets against the answer
+    pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+        Pattern {
+            code: self.row(guess).get(answer),
+            len: self.length as u8,
+        }
+    }
+
+    /// pattern codes of a guess against every answer
+    pub fn row(&self, guess: usize) -> Row<'_> {
+        let size = self.answers * self.width;
+        Row {
+            codes: &self.codes[guess * size..(guess + 1) * size],
+            width: self.width,
+        }
+    }
+}
+
+/// location of the cache file for a word list key
+pub fn cache_path(dir: impl AsRef<Path>, key: u64) -> PathBuf {
+    dir.as_ref().join(format!("patterns-{:016x}.bin", key))
+}
+
+/// deletes the cache files of other word lists from `dir`, along with temp
+/// files left behind by interrupted saves, keeping only `keep`
+///
+/// errors are ignored, since another process may be cleaning up too and a
+/// file left behind is only wasted space
+fn remove_stale(dir: &Path, keep: &Path) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if !name.starts_with("patterns-") || path == keep {
+            continue;
+        }
+        let stale = if name.ends_with(".bin") {
+            true
+        } else if name.ends_with(".tmp") {
+            entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_TMP)
+        } else {
+            false
+        };
+        if stale {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// hashes the word lists together with the feedback rules, so any change to
+/// either gives a different cache file
+pub fn cache_key(guesses: &[String], answers: &[String]) -> u64 {
+    // 64-bit FNV-1a, which unlike std's hasher is stable across releases
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    feed(&RULES_VERSION.to_le_bytes());
+    feed(&(word_length(guesses, answers) as u64).to_le_bytes());
+    for list in [guesses, answers] {
+        feed(&(list.len() as u64).to_le_bytes());
+        for word in list {
+            feed(word.as_bytes());
+            feed(b"\n");
+        }
+    }
+    hash
+}
+
+fn header(key: u64, guesses: usize, answers: usize) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..16].copy_from_slice(&key.to_le_bytes());
+    header[16..24].copy_from_slice(&(guesses as u64).to_le_bytes());
+    header[24..].copy_from_slice(&(answers as u64).to_le_bytes());
+    header
+}
+
+/// length of the words, taken from the first one
+fn word_length(guesses: &[String], answers: &[String]) -> usize {
+    guesses.iter().chain(answers).next().map_or(0, String::len)
+}
+
+fn encode_or_panic(word: &str, length: usize) -> &[u8] {
+    encode(word, length).unwrap_or_else(|| panic!("invalid word {:?}", word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::time::SystemTime;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    /// empty directory of its own for a test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("wordle-matrix-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn cache_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn assert_same(a: &PatternMatrix, b: &PatternMatrix) {
+        assert_eq!(a.guess_count(), b.guess_count());
+        assert_eq!(a.answer_count(), b.answer_count());
+        assert_eq!(&a.codes[..], &b.codes[..]);
+    }
+
+    #[test]
+    fn second_load_maps_the_cache() {
+        let dir = temp_dir("mapped");
+        let list = words(&["crane", "eerie", "there", "speed"]);
+        let built = PatternMatrix::cached(&list, &list, &dir).unwrap();
+        let loaded = PatternMatrix::cached(&list, &list, &dir).unwrap();
+        assert!(matches!(loaded.codes, Codes::Mapped(_)));
+        assert_same(&loaded, &PatternMatrix::new(&list, &list));
+        assert_same(&built, &loaded);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn changed_list_replaces_the_cache() {
+        let dir = temp_dir("changed");
+        let first = words(&["crane", "eerie", "there"]);
+        let second = words(&["crane", "eerie", "speed"]);
+        PatternMatrix::cached(&first, &first, &dir).unwrap();
+        let matrix = PatternMatrix::cached(&second, &second, &dir).unwrap();
+        assert_same(&matrix, &PatternMatrix::new(&second, &second));
+        let key = cache_key(&second, &second);
+        assert_eq!(cache_files(&dir), vec![cache_path(&dir, key)]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_cache_is_rebuilt() {
+        let dir = temp_dir("corrupt");
+        let list = words(&["crane", "eerie", "there", "speed"]);
+        let path = cache_path(&dir, cache_key(&list, &list));
+        PatternMatrix::cached(&list, &list, &dir).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        let matrix = PatternMatrix::cached(&list, &list, &dir).unwrap();
+        assert_same(&matrix, &PatternMatrix::new(&list, &list));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        let mut garbage = bytes.clone();
+        garbage[0] ^= 0xff;
+        fs::write(&path, garbage).unwrap();
+        let matrix = PatternMatrix::cached(&list, &list, &dir).unwrap();
+        assert_same(&matrix, &PatternMatrix::new(&list, &list));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn only_stale_temp_files_are_removed() {
+        let dir = temp_dir("stale");
+        let old = dir.join("patterns-0000000000000000.1-00000000.tmp");
+        let fresh = dir.join("patterns-0000000000000000.2-00000000.tmp");
+        let other = dir.join("words.txt");
+        for path in [&old, &fresh, &other] {
+            fs::write(path, b"").unwrap();
+        }
+        let long_ago = SystemTime::now() - STALE_TMP * 2;
+        File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(long_ago)
+            .unwrap();
+        let list = words(&["crane", "eerie"]);
+        PatternMatrix::cached(&list, &list, &dir).unwrap();
+        let key = cache_key(&list, &list);
+        let mut expected = vec![cache_path(&dir, key), fresh, other];
+        expected.sort();
+        assert_eq!(cache_files(&dir), expected);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// how corpus frequencies are turned into a prior weight for each answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prior {
+    /// every answer is equally likely
+    Uniform,
+    /// weight proportional to the corpus count, uniform for words without one
+    Frequency,
+    /// logistic curve over frequency rank: answers ranked well before
+    /// `center` are near certain, those well after it are unlikely, and
+    /// `width` sets how many ranks the drop off takes. both must be finite
+    /// and `width` positive
+    Sigmoid { center: f64, width: f64 },
+}
+
+impl Prior {
+    /// default rank where the sigmoid weight halves
+    pub const SIGMOID_CENTER: f64 = 3000.0;
+
+    /// default sigmoid width in ranks
+    pub const SIGMOID_WIDTH: f64 = 500.0;
+
+    /// checks that a list's counts carry what the prior needs: every word's
+    /// count for `frequency`, and counts that never increase for `sigmoid`
+    pub fn check(self, counts: &[Option<u64>]) -> Result<(), String> {
+        if self == Prior::Uniform {
+            return Ok(());
+        }
+        if counts.iter().any(Option::is_none) {
+            return Err(format!(
+                "the {} prior needs a word list with frequencies",
+                self
+            ));
+        }
+        let ordered = counts.windows(2).all(|pair| pair[0] >= pair[1]);
+        if matches!(self, Prior::Sigmoid { .. }) && !ordered {
+            return Err(format!(
+                "the {} prior needs a word list ordered by frequency",
+                self
+            ));
+        }
+        Ok(())
+    }
+
+    /// weight of each word, given the words' corpus counts in rank order
+    pub fn weights(self, counts: &[Option<u64>]) -> Vec<f64> {
+        match self {
+            Prior::Uniform => vec![1.0; counts.len()],
+            Prior::Frequency => counts
+                .iter()
+                .map(|count| count.map_or(1.0, |c| c.max(1) as f64))
+                .collect(),
+            Prior::Sigmoid { center, width } => (0..counts.len())
+                .map(|rank| 1.0 / (1.0 + ((rank as f64 - center) / width).exp()))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for Prior {
+    type Err = String;
+
+    /// parses "uniform", "frequency" or "sigmoid[:center[:width]]", where
+    /// center and width are finite and width is positive
+    fn from_str(s: &str) -> Result<Prior, String> {
+        let mut parts = s.split(':');
+        let invalid = || {
+            format!(
+                "unknown prior {:?}, expected uniform, frequency or sigmoid[:center[:width]]",
+                s
+            )
+        };
+        let prior = match parts.next().unwrap_or_default() {
+            "uniform" => Prior::Uniform,
+            "frequency" => Prior::Frequency,
+            "sigmoid" => {
+                let mut number = |default| match parts.next() {
+                    Some(n) => match n.parse::<f64>() {
+                        Ok(n) if n.is_finite() => Ok(n),
+                        _ => Err(invalid()),
+                    },
+                    None => Ok(default),
+                };
+                let center = number(Prior::SIGMOID_CENTER)?;
+                let width = number(Prior::SIGMOID_WIDTH)?;
+                if width <= 0.0 {
+                    return Err(invalid());
+                }
+                Prior::Sigmoid { center, width }
+            }
+            _ => return Err(invalid()),
+        };
+        match parts.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(prior),
+        }
+    }
+}
+
+impl fmt::Display for Prior {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Prior::Uniform => write!(f, "uniform"),
+            Prior::Frequency => write!(f, "frequency"),
+            Prior::Sigmoid { center, width } => write!(f, "sigmoid:{}:{}", center, width),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_priors() {
+        assert_eq!("uniform".parse(), Ok(Prior::Uniform));
+        assert_eq!("frequency".parse(), Ok(Prior::Frequency));
+        let sigmoid = Prior::Sigmoid {
+            center: Prior::SIGMOID_CENTER,
+            width: Prior::SIGMOID_WIDTH,
+        };
+        assert_eq!("sigmoid".parse(), Ok(sigmoid));
+        let sigmoid = Prior::Sigmoid {
+            center: 100.0,
+            width: 20.5,
+        };
+        assert_eq!("sigmoid:100:20.5".parse(), Ok(sigmoid));
+        assert_eq!(sigmoid.to_string().parse(), Ok(sigmoid));
+    }
+
+    #[test]
+    fn rejects_bad_priors() {
+        for s in [
+            "",
+            "normal",
+            "uniform:1",
+            "sigmoid:x",
+            "sigmoid:nan",
+            "sigmoid:inf",
+            "sigmoid:100:-inf",
+            "sigmoid:100:0",
+            "sigmoid:100:-5",
+            "sigmoid:100:20:1",
+        ] {
+            assert!(s.parse::<Prior>().is_err(), "{:?} parsed", s);
+        }
+    }
+
+    #[test]
+    fn sigmoid_halves_at_center() {
+        let prior = Prior::Sigmoid {
+            center: 2.0,
+            width: 1.0,
+        };
+        let weights = prior.weights(&[None; 5]);
+        assert_eq!(weights[2], 0.5);
+        assert!(weights.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::feedback::{get_pattern, Pattern};
+use crate::filter::narrow_candidates;
+use crate::game::{GameState, Mode};
+use crate::matrix::PatternMatrix;
+use crate::strategy::Strategy;
+use crate::words::WordLists;
+
+/// number of guesses wordle allows
+pub const DEFAULT_MAX_TURNS: u32 = 6;
+
+/// rules a game is played under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// how strictly guesses must follow the hints
+    pub mode: Mode,
+    /// guesses allowed before the game is lost
+    pub max_turns: u32,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            mode: Mode::Normal,
+            max_turns: DEFAULT_MAX_TURNS,
+        }
+    }
+}
+
+/// how a solve ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// the last guess was the target
+    Solved,
+    /// every allowed turn was used without finding the target
+    OutOfTurns,
+    /// no answer fit the feedback, so the target isn't in the word list
+    NoCandidates,
+}
+
+/// a guess made while solving
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    pub guess: String,
+    pub pattern: Pattern,
+    /// candidate answers left after the guess
+    pub remaining: usize,
+}
+
+/// record of a single solve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveResult {
+    pub target: String,
+    pub outcome: Outcome,
+    /// every guess in order
+    pub turns: Vec<Turn>,
+}
+
+impl SolveResult {
+    /// determines if the target was found
+    pub fn is_solved(&self) -> bool {
+        self.outcome == Outcome::Solved
+    }
+
+    /// number of guesses made
+    pub fn turn_count(&self) -> u32 {
+        self.turns.len() as u32
+    }
+}
+
+/// solves a wordle until it finds the word or gives up
+///
+/// `words` are the words behind the rows and columns of `matrix`, and the
+/// target doesn't have to be one of the answers. every guess follows the
+/// rules of `options`
+pub fn solve(
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    target: &str,
+    strategy: &dyn Strategy,
+    options: &Options,
+) -> SolveResult {
+    let mut state = GameState::with_mode(options.mode);
+    let mut candidates: Vec<usize> = (0..words.answers.len()).collect();
+    let mut result = SolveResult {
+        target: target.to_string(),
+        outcome: Outcome::OutOfTurns,
+        turns: Vec::new(),
+    };
+    loop {
+        let turn = state.turn();
+        let guess = strategy.next_guess(&state, words, matrix, &candidates);
+        let pattern = get_pattern(&words.guesses[guess], target);
+        candidates = narrow_candidates(matrix, &candidates, guess, pattern);
+        state.push(words.guesses[guess].clone(), pattern);
+        result.turns.push(Turn {
+            guess: words.guesses[guess].clone(),
+            pattern,
+            remaining: candidates.len(),
+        });
+        if pattern.is_win() {
+            result.outcome = Outcome::Solved;
+            return result;
+        }
+        // checked first, since a target missing from the list isn't helped
+        // by more turns
+        if candidates.is_empty() {
+            result.outcome = Outcome::NoCandidates;
+            return result;
+        }
+        if turn >= options.max_turns {
+            result.outcome = Outcome::OutOfTurns;
+            return result;
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::feedback::Pattern;
+use crate::game::GameState;
+use crate::matrix::PatternMatrix;
+use crate::words::WordLists;
+
+/// chooses the next guess for a game in progress
+pub trait Strategy {
+    /// returns the row in `matrix` of the next word to guess, given the
+    /// game so far and the columns of the words that are still possible
+    /// answers (most frequent first)
+    ///
+    /// rows and columns are laid out like `words.guesses` and
+    /// `words.answers`, so answer column `i` is also guess row `i`
+    fn next_guess(
+        &self,
+        state: &GameState,
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        candidates: &[usize],
+    ) -> usize;
+}
+
+/// guesses the most likely remaining word under the prior, which is the most
+/// frequent one unless a prior says otherwise
+pub struct Frequency;
+
+impl Strategy for Frequency {
+    fn next_guess(
+        &self,
+        _state: &GameState,
+        words: &WordLists,
+        _matrix: &PatternMatrix,
+        candidates: &[usize],
+    ) -> usize {
+        most_likely(words, candidates)
+    }
+}
+
+/// guesses the word whose feedback patterns carry the most expected
+/// information about the remaining words, weighing each candidate answer by
+/// its prior and preferring likely candidates on ties
+pub struct Entropy;
+
+impl Strategy for Entropy {
+    fn next_guess(
+        &self,
+        state: &GameState,
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        candidates: &[usize],
+    ) -> usize {
+        if candidates.len() <= 2 {
+            return most_likely(words, candidates);
+        }
+
+        let mut buckets = Buckets::new(matrix);
+        let mut best = candidates[0];
+        let mut best_entropy = f64::MIN;
+        for guess in guess_order(state, words, matrix, candidates) {
+            buckets.fill(words, matrix, guess, candidates);
+            let entropy = buckets.entropy();
+            if entropy > best_entropy {
+                best_entropy = entropy;
+                best = guess;
+            }
+        }
+        best
+    }
+}
+
+/// guesses the word whose largest feedback bucket over the remaining words
+/// is smallest, breaking ties by the number of buckets and then by frequency
+pub struct Minimax;
+
+impl Strategy for Minimax {
+    fn next_guess(
+        &self,
+        state: &GameState,
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        candidates: &[usize],
+    ) -> usize {
+        if candidates.len() <= 2 {
+            return most_likely(words, candidates);
+        }
+
+        let mut buckets = Buckets::new(matrix);
+        let mut best = candidates[0];
+        let mut best_score = (u32::MAX, 0);
+        for guess in guess_order(state, words, matrix, candidates) {
+            buckets.fill(words, matrix, guess, candidates);
+            let largest = buckets.iter().map(|(_, n, _)| n).max().unwrap_or(0);
+            let used = buckets.len();
+            if largest < best_score.0 || (largest == best_score.0 && used > best_score.1) {
+                best_score = (largest, used);
+                best = guess;
+            }
+        }
+        best
+    }
+}
+
+/// the candidate with the highest prior weight, the earliest one on ties
+pub fn most_likely(words: &WordLists, candidates: &[usize]) -> usize {
+    let mut best = candidates[0];
+    for &c in candidates {
+        if words.weights[c] > words.weights[best] {
+            best = c;
+        }
+    }
+    best
+}
+
+/// every guess row the game's mode allows, with the candidates first from
+/// most to least likely so they win ties against words that can't be the
+/// answer
+///
+/// candidates fit every hint so far, so they are always allowed
+fn guess_order(
+    state: &GameState,
+    words: &WordLists,
+    matrix: &PatternMatrix,
+    candidates: &[usize],
+) -> Vec<usize> {
+    let mut is_candidate = vec![false; matrix.guess_count()];
+    for &c in candidates {
+        is_candidate[c] = true;
+    }
+    let rules = state.rules();
+    let mut order = candidates.to_vec();
+    order.sort_by(|&a, &b| words.weights[b].total_cmp(&words.weights[a]));
+    order.extend(
+        (0..matrix.guess_count()).filter(|&g| !is_candidate[g] && rules.allows(&words.guesses[g])),
+    );
+    order
+}
+
+/// how the candidates split by feedback pattern for one guess at a time
+///
+/// there are too many patterns for long words to clear them all for every
+/// guess, so only the patterns the last guess produced get reset
+pub struct Buckets {
+    counts: Vec<u32>,
+    weights: Vec<f64>,
+    used: Vec<u32>,
+}
+
+impl Buckets {
+    /// empty buckets for every pattern of the matrix's word length
+    pub fn new(matrix: &PatternMatrix) -> Buckets {
+        let count = Pattern::count(matrix.word_length());
+        Buckets {
+            counts: vec![0; count],
+            weights: vec![0.0; count],
+            used: Vec::new(),
+        }
+    }
+
+    /// splits the candidates by the pattern they give for `guess`, counting
+    /// them and summing their prior weights
+    pub fn fill(
+        &mut self,
+        words: &WordLists,
+        matrix: &PatternMatrix,
+        guess: usize,
+        candidates: &[usize],
+    ) {
+        for &code in &self.used {
+            self.counts[code as usize] = 0;
+            self.weights[code as usize] = 0.0;
+        }
+        self.used.clear();
+        let row = matrix.row(guess);
+        for &answer in candidates {
+            let code = row.get(answer);
+            if self.counts[code as usize] == 0 {
+                self.used.push(code);
+            }
+            self.counts[code as usize] += 1;
+            self.weights[code as usize] += words.weights[answer];
+        }
+    }
+
+    /// number of patterns at least one candidate gave
+    pub fn len(&self) -> usize {
+        self.used.len()
+    }
+
+    /// determines if there were no candidates
+    pub fn is_empty(&self) -> bool {
+        self.used.is_empty()
+    }
+
+    /// expected information in bits from seeing the pattern, weighing each
+    /// candidate by its prior
+    pub fn entropy(&self) -> f64 {
+        let total: f64 = self.iter().map(|(_, _, w)| w).sum();
+        self.iter()
+            .map(|(_, _, w)| w)
+            .filter(|&w| w > 0.0)
+            .map(|w| {
+                let p = w / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// pattern code, candidate count and total weight of every non-empty
+    /// bucket, in the order the patterns were first seen
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u32, f64)> + '_ {
+        self.used.iter().map(|&code| {
+            let i = code as usize;
+            (code, self.counts[i], self.weights[i])
+        })
+    }
+}
@@ -0,0 +1,528 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::collections::HashMap;
+use std::io::{self, prelude::*};
+
+use crate::commands::{Command, HELP};
+use wordle_solver::game::parse_played;
+use wordle_solver::{
+    get_hints, narrow_by_word, replay, Feedback, GameState, Options, Pattern, PatternMatrix,
+    Strategy, WordLists,
+};
+
+/// keyboard rows as drawn on screen
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// columns each tile takes, including the gap after it
+const TILE_WIDTH: u16 = 4;
+
+/// columns the suggestion panel needs beside the grid
+const PANEL_WIDTH: u16 = 24;
+
+/// who knows the answer
+pub enum Role<'a> {
+    /// the solver suggests guesses and the user types the colours they got
+    Assistant,
+    /// the program picked `target` and scores the user's guesses
+    Host { target: &'a str },
+}
+
+/// a full-screen game, either assisting or hosting
+pub struct App<'a> {
+    words: &'a WordLists,
+    matrix: &'a PatternMatrix,
+    strategy: &'a dyn Strategy,
+    options: &'a Options,
+    length: usize,
+    role: Role<'a>,
+    state: GameState,
+    candidates: Vec<usize>,
+    /// guess row the strategy would play next
+    suggestion: Option<usize>,
+    /// colours, or a guess and its colours (assistant), or letters (host)
+    /// typed for the current row
+    input: String,
+    message: String,
+    done: bool,
+    /// most likely words the panel lists, as many as fit if not set
+    top: Option<usize>,
+}
+
+impl<'a> App<'a> {
+    /// sets up a game where every answer is still possible
+    pub fn new(
+        words: &'a WordLists,
+        matrix: &'a PatternMatrix,
+        strategy: &'a dyn Strategy,
+        options: &'a Options,
+        length: usize,
+        role: Role<'a>,
+    ) -> App<'a> {
+        App {
+            words,
+            matrix,
+            strategy,
+            options,
+            length,
+            role,
+            state: GameState::with_mode(options.mode),
+            candidates: (0..words.answers.len()).collect(),
+            suggestion: None,
+            input: String::new(),
+            message: String::new(),
+            done: false,
+            top: None,
+        }
+    }
+
+    /// takes over the terminal until the user quits, redrawing on every key
+    /// press and resize
+    pub fn run(mut self) -> io::Result<()> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        let _restore = Restore;
+        execute!(out, EnterAlternateScreen, Hide)?;
+        self.suggest();
+        loop {
+            self.draw(&mut out)?;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release && !self.handle(key) => {
+                    return Ok(());
+                }
+                // the next draw picks up the new size
+                Event::Resize(..) => {}
+                _ => {}
+            }
+        }
+    }
+
+    /// applies a key press, returning false to quit
+    fn handle(&mut self, key: KeyEvent) -> bool {
+        let ctrl_c =
+            key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+        if key.code == KeyCode::Esc || ctrl_c {
+            return false;
+        }
+        match key.code {
+            KeyCode::Enter if self.done && self.input.is_empty() => return false,
+            KeyCode::Enter if self.input.starts_with(':') => {
+                match self.input.parse() {
+                    Ok(command) => self.command(command),
+                    Err(e) => self.message = e,
+                }
+                self.input.clear();
+            }
+            KeyCode::Enter if self.done => {
+                self.message = match self.role {
+                    Role::Host { .. } => "game over, press enter to quit",
+                    Role::Assistant => "game over, :undo or :reset to keep going",
+                }
+                .to_string();
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) => {
+                let c = c.to_ascii_lowercase();
+                let accepted = match self.role {
+                    // commands only assist, so a host game can't be undone
+                    Role::Assistant if self.input.starts_with(':') || c == ':' => {
+                        self.input.is_empty() || c != ':'
+                    }
+                    // room for "<guess> <colours>"
+                    Role::Assistant => {
+                        (c.is_ascii_lowercase() || (c == ' ' && !self.input.contains(' ')))
+                            && self.input.len() < self.length * 2 + 1
+                    }
+                    Role::Host { .. } => c.is_ascii_lowercase() && self.input.len() < self.length,
+                };
+                if accepted {
+                    self.input.push(c);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// carries out a command typed in place of a hint
+    fn command(&mut self, command: Command) {
+        match command {
+            Command::Undo => {
+                self.message = match self.state.undo() {
+                    Some((word, pattern)) => format!("undid {} {}", word, pattern),
+                    None => "nothing to undo".to_string(),
+                };
+            }
+            Command::Reset => {
+                self.state.reset();
+                self.message = "starting over".to_string();
+            }
+            Command::History if self.state.history.is_empty() => {
+                self.message = "no guesses yet".to_string();
+            }
+            Command::History => {
+                let rows: Vec<String> = self
+                    .state
+                    .history
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (word, pattern))| format!("{}: {} {}", i + 1, word, pattern))
+                    .collect();
+                self.message = rows.join(", ");
+            }
+            Command::Top(n) => {
+                self.top = Some(n);
+                self.message = format!("listing the top {} words", n);
+            }
+            Command::Candidates => {
+                let all: Vec<&str> = self
+                    .candidates
+                    .iter()
+                    .map(|&c| self.words.answers[c].as_str())
+                    .collect();
+                self.message = format!("possible words: {}", all.join(" "));
+            }
+            Command::Help => self.message = HELP.to_string(),
+        }
+        if matches!(command, Command::Undo | Command::Reset) {
+            self.candidates = replay(self.words, self.matrix, &self.state);
+            self.done = false;
+            self.suggest();
+        }
+    }
+
+    /// plays the current row once it is fully typed
+    fn submit(&mut self) {
+        match self.role {
+            Role::Assistant => {
+                let suggested = match self.suggestion {
+                    Some(g) => &self.words.guesses[g],
+                    None => return,
+                };
+                let (word, pattern) = match parse_played(&self.input, suggested, self.length) {
+                    Ok(played) => played,
+                    Err(e) => {
+                        self.message = format!("invalid hint string: {}", e);
+                        return;
+                    }
+                };
+                if let Err(e) = self.state.check(&word) {
+                    self.message = format!("not allowed in {} mode: {}", self.options.mode, e);
+                    return;
+                }
+                self.play(word, pattern);
+            }
+            Role::Host { target } => {
+                let word = self.input.clone();
+                if word.len() != self.length {
+                    self.message = format!("guess must have {} letters", self.length);
+                    return;
+                }
+                if self.words.guess_index(&word).is_none() {
+                    self.message = format!("not in word list: {}", word);
+                    return;
+                }
+                if let Err(e) = self.state.check(&word) {
+                    self.message = format!("not allowed in {} mode: {}", self.options.mode, e);
+                    return;
+                }
+                let kinds: Vec<Feedback> =
+                    get_hints(&word, target).iter().map(|h| h.kind).collect();
+                self.play(word, Pattern::new(&kinds));
+            }
+        }
+    }
+
+    /// records a guess and its pattern, then checks whether the game is over
+    fn play(&mut self, word: String, pattern: Pattern) {
+        self.input.clear();
+        self.candidates = narrow_by_word(self.words, self.matrix, &self.candidates, &word, pattern);
+        self.state.push(word, pattern);
+        let turns = self.state.history.len();
+        self.done = true;
+        if pattern.is_win() {
+            self.message = format!("solved in {} turns! press enter to quit", turns);
+        } else if turns as u32 >= self.options.max_turns {
+            self.message = match self.role {
+                Role::Host { target } => format!("out of turns, the word was {}", target),
+                Role::Assistant => format!("out of turns after {}", turns),
+            };
+        } else if self.candidates.is_empty() {
+            self.message =
+                "word not found, :undo a mistyped hint or try sourcing more words with --count arg"
+                    .to_string();
+        } else {
+            self.done = false;
+            self.message.clear();
+            self.suggest();
+        }
+    }
+
+    /// asks the strategy for the next guess
+    fn suggest(&mut self) {
+        self.suggestion = if self.candidates.is_empty() {
+            None
+        } else {
+            Some(
+                self.strategy
+                    .next_guess(&self.state, self.words, self.matrix, &self.candidates),
+            )
+        };
+    }
+
+    /// redraws the whole screen for the current terminal size
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+
+        let rows = self.options.max_turns as u16;
+        let board_width =
+            (self.length as u16 * TILE_WIDTH).max(KEYBOARD[0].len() as u16 * TILE_WIDTH);
+        let keyboard_top = 2 + rows + 1;
+        let beside = width >= 2 + board_width + 2 + PANEL_WIDTH;
+        let panel_lines = if beside { 0 } else { 4 };
+        if width < 2 + board_width || height < keyboard_top + 3 + panel_lines + 3 {
+            queue!(
+                out,
+                Print("terminal too small, resize or press esc to quit")
+            )?;
+            return out.flush();
+        }
+
+        let title = match self.role {
+            Role::Assistant => "wordle assistant",
+            Role::Host { .. } => "wordle",
+        };
+        queue!(
+            out,
+            MoveTo(2, 0),
+            Print(format!(
+                "{} ({} mode) turn {}/{}",
+                title,
+                self.options.mode,
+                self.state.turn().min(self.options.max_turns),
+                self.options.max_turns
+            ))
+        )?;
+
+        let grid_left = 2 + (board_width - self.length as u16 * TILE_WIDTH) / 2;
+        for row in 0..rows {
+            queue!(out, MoveTo(grid_left, 2 + row))?;
+            self.draw_row(out, row as usize)?;
+        }
+        self.draw_keyboard(out, 2, keyboard_top, board_width)?;
+
+        let (panel_left, panel_top, panel_bottom) = if beside {
+            (2 + board_width + 2, 2, height - 3)
+        } else {
+            let top = keyboard_top + 4;
+            (2, top, height - 3)
+        };
+        self.draw_panel(out, panel_left, panel_top, panel_bottom)?;
+
+        let help = match self.role {
+            Role::Assistant => {
+                "type colours (bygbb) or guess and colours (crane bygbb), :help, esc to quit"
+            }
+            Role::Host { .. } => "type a guess, enter to submit, esc to quit",
+        };
+        // a command being typed shows in place of the last message
+        let status = if self.input.starts_with(':') {
+            &self.input
+        } else {
+            &self.message
+        };
+        queue!(
+            out,
+            MoveTo(2, height - 2),
+            Print(fit(status, width - 2)),
+            MoveTo(2, height - 1),
+            SetForegroundColor(Color::DarkGrey),
+            Print(fit(help, width - 2)),
+            ResetColor
+        )?;
+        out.flush()
+    }
+
+    /// draws a row of the grid: a played guess, the row being typed, or an
+    /// empty row
+    fn draw_row(&self, out: &mut impl Write, row: usize) -> io::Result<()> {
+        if let Some((guess, pattern)) = self.state.history.get(row) {
+            for (pos, letter) in guess.chars().enumerate() {
+                tile(out, letter, Some(pattern.get(pos)))?;
+            }
+            return Ok(());
+        }
+        let current = row == self.state.history.len() && !self.done;
+        let (letters, colours) = match (&self.role, current) {
+            (Role::Assistant, true) => self.typed_row(),
+            (Role::Host { .. }, true) => (self.input.clone(), String::new()),
+            _ => (String::new(), String::new()),
+        };
+        for pos in 0..self.length {
+            let letter = letters.chars().nth(pos).unwrap_or('_');
+            let kind = colours
+                .chars()
+                .nth(pos)
+                .and_then(|c| Feedback::try_from(c).ok());
+            tile(out, letter, kind)?;
+        }
+        Ok(())
+    }
+
+    /// letters and colours to show for the row being typed in assistant
+    /// mode: colours alone apply to the suggestion, otherwise the typed
+    /// guess comes first
+    fn typed_row(&self) -> (String, String) {
+        let suggested = self
+            .suggestion
+            .map(|g| self.words.guesses[g].clone())
+            .unwrap_or_default();
+        if self.input.starts_with(':') {
+            return (suggested, String::new());
+        }
+        match self.input.split_once(' ') {
+            Some((word, colours)) => (word.to_string(), colours.to_string()),
+            None if self.input.chars().all(|c| matches!(c, 'g' | 'y' | 'b')) => {
+                (suggested, self.input.clone())
+            }
+            None => (self.input.clone(), String::new()),
+        }
+    }
+
+    /// draws the keyboard with the best known feedback for every letter
+    fn draw_keyboard(
+        &self,
+        out: &mut impl Write,
+        left: u16,
+        top: u16,
+        width: u16,
+    ) -> io::Result<()> {
+        let known = self.letter_states();
+        for (i, keys) in KEYBOARD.iter().enumerate() {
+            let indent = (width - keys.len() as u16 * TILE_WIDTH) / 2;
+            queue!(out, MoveTo(left + indent, top + i as u16))?;
+            for key in keys.chars() {
+                match known.get(&key) {
+                    Some(&kind) => tile(out, key, Some(kind))?,
+                    None => queue!(
+                        out,
+                        SetBackgroundColor(Color::Grey),
+                        SetForegroundColor(Color::Black),
+                        Print(format!(" {} ", key.to_ascii_uppercase())),
+                        ResetColor,
+                        Print(" ")
+                    )?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// draws the candidate count, the suggested guess and the most likely
+    /// answers, as many as fit above `bottom`
+    fn draw_panel(&self, out: &mut impl Write, left: u16, top: u16, bottom: u16) -> io::Result<()> {
+        queue!(
+            out,
+            MoveTo(left, top),
+            Print(format!("possible words: {}", self.candidates.len()))
+        )?;
+        if let (Some(guess), false) = (self.suggestion, self.done) {
+            queue!(
+                out,
+                MoveTo(left, top + 1),
+                Print(format!("try: {}", self.words.guesses[guess]))
+            )?;
+        }
+        if top + 4 >= bottom {
+            return Ok(());
+        }
+
+        let mut likely: Vec<(usize, f64)> = self
+            .candidates
+            .iter()
+            .copied()
+            .zip(self.words.probabilities(&self.candidates))
+            .collect();
+        likely.sort_by(|a, b| b.1.total_cmp(&a.1));
+        queue!(out, MoveTo(left, top + 3), Print("likely:"))?;
+        let room = (bottom - top - 4) as usize;
+        let shown = self.top.map_or(room, |top| top.min(room));
+        for (i, (answer, p)) in likely.iter().take(shown).enumerate() {
+            queue!(
+                out,
+                MoveTo(left, top + 4 + i as u16),
+                Print(format!(
+                    "  {} {:.1}%",
+                    self.words.answers[*answer],
+                    p * 100.0
+                ))
+            )?;
+        }
+        Ok(())
+    }
+
+    /// strongest feedback each letter has had: green, then yellow, then absent
+    fn letter_states(&self) -> HashMap<char, Feedback> {
+        let rank = |kind| match kind {
+            Feedback::Black => 0,
+            Feedback::Yellow => 1,
+            Feedback::Green => 2,
+        };
+        let mut known = HashMap::new();
+        for (guess, pattern) in &self.state.history {
+            for (pos, letter) in guess.chars().enumerate() {
+                let kind = pattern.get(pos);
+                let best = known.entry(letter).or_insert(kind);
+                if rank(kind) > rank(*best) {
+                    *best = kind;
+                }
+            }
+        }
+        known
+    }
+}
+
+/// cuts text down to `width` columns, marking where it was cut
+fn fit(text: &str, width: u16) -> String {
+    let width = width as usize;
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(3)).collect();
+    cut.push_str("...");
+    cut
+}
+
+/// draws one letter tile coloured by its feedback, followed by a gap
+fn tile(out: &mut impl Write, letter: char, kind: Option<Feedback>) -> io::Result<()> {
+    let (background, foreground) = match kind {
+        Some(Feedback::Green) => (Color::Green, Color::Black),
+        Some(Feedback::Yellow) => (Color::Yellow, Color::Black),
+        Some(Feedback::Black) => (Color::DarkGrey, Color::White),
+        None => (Color::Reset, Color::Grey),
+    };
+    queue!(
+        out,
+        SetBackgroundColor(background),
+        SetForegroundColor(foreground),
+        Print(format!(" {} ", letter.to_ascii_uppercase())),
+        ResetColor,
+        Print(" ")
+    )
+}
+
+/// puts the terminal back the way it was, even if the game panics
+struct Restore;
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+        let _ = terminal::disable_raw_mode();
+    }
+}
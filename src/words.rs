@@ -0,0 +1,251 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, prelude::*, BufReader};
+use std::path::{Path, PathBuf};
+
+use flate2::bufread::GzDecoder;
+
+use crate::feedback::DEFAULT_LENGTH;
+use crate::format::{read_entries, Entry, ListFormat};
+use crate::matrix::encode;
+use crate::prior::Prior;
+
+/// name of the downloaded word list inside the cache directory
+pub const FILENAME: &str = "words.txt";
+
+/// where `wordle fetch` downloads the word list from by default
+pub const DOWNLOAD_URL: &str = "https://norvig.com/ngrams/count_1w.txt";
+
+/// first bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// wordle answer list compiled into the binary as `word<TAB>frequency`,
+/// most frequent first
+pub const BUNDLED_ANSWERS: &str = include_str!("../data/answers.tsv");
+
+/// wordle accepted guess list compiled into the binary, one word per line
+pub const BUNDLED_GUESSES: &str = include_str!("../data/guesses.txt");
+
+/// per-user directory for the downloaded word list and pattern caches:
+/// `$XDG_CACHE_HOME/wordle`, `~/.cache/wordle` or `%LOCALAPPDATA%\wordle`,
+/// falling back to the working directory
+pub fn cache_dir() -> PathBuf {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from));
+    match base {
+        Some(dir) => dir.join("wordle"),
+        None => PathBuf::from("."),
+    }
+}
+
+/// location `wordle fetch` saves the word list to by default
+pub fn default_words_path() -> PathBuf {
+    cache_dir().join(FILENAME)
+}
+
+/// downloads a list of words ordered by how frequently they are used,
+/// creating the destination's directory if needed
+pub async fn download_words(url: &str, path: impl AsRef<Path>) -> io::Result<()> {
+    if let Some(dir) = path.as_ref().parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let resp = reqwest::get(url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(request_error)?;
+    // raw bytes, since the list may be gzip compressed
+    let body = resp.bytes().await.map_err(request_error)?;
+    fs::write(path, body)
+}
+
+fn request_error(e: reqwest::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+/// reads a word file and parses the top `count` words of `length` letters
+/// into a vector, most frequent first, skipping words that aren't all
+/// lowercase letters
+///
+/// gzip compressed files are decompressed transparently. without an
+/// explicit format it is guessed from the extension, then from the contents
+pub fn parse_words(
+    path: impl AsRef<Path>,
+    format: Option<ListFormat>,
+    length: usize,
+    count: u64,
+) -> io::Result<Vec<String>> {
+    let entries = parse_entries(path, format, length, count)?;
+    Ok(entries.into_iter().map(|entry| entry.word).collect())
+}
+
+/// like [`parse_words`], but keeps each word's corpus frequency
+pub fn parse_entries(
+    path: impl AsRef<Path>,
+    format: Option<ListFormat>,
+    length: usize,
+    count: u64,
+) -> io::Result<Vec<Entry>> {
+    let path = path.as_ref();
+    let format = format.or_else(|| ListFormat::from_path(path));
+    let read = || {
+        let mut reader = BufReader::new(File::open(path)?);
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            read_words(
+                BufReader::new(GzDecoder::new(reader)),
+                format,
+                length,
+                count,
+            )
+        } else {
+            read_words(reader, format, length, count)
+        }
+    };
+    read().map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+/// parses the top `count` words of `length` letters from a word list,
+/// guessing the format from the contents if it isn't given
+pub fn read_words(
+    mut reader: impl BufRead,
+    format: Option<ListFormat>,
+    length: usize,
+    count: u64,
+) -> io::Result<Vec<Entry>> {
+    let format = match format {
+        Some(f) => f,
+        None => ListFormat::sniff(reader.fill_buf()?),
+    };
+    let entries = read_entries(reader, format)?
+        .into_iter()
+        .filter(|entry| encode(&entry.word, length).is_some())
+        .take(count.try_into().unwrap_or(usize::MAX))
+        .collect();
+    Ok(entries)
+}
+
+/// top `count` answers and every guess of the lists compiled into the binary,
+/// which only have five letter words
+pub fn bundled_words(count: u64) -> WordLists {
+    let answers = bundled_answers(count);
+    WordLists::new(
+        answers.into_iter().map(|entry| entry.word).collect(),
+        bundled_guesses(),
+    )
+}
+
+/// top `count` answers compiled into the binary along with their corpus
+/// frequencies, most frequent first
+pub fn bundled_answers(count: u64) -> Vec<Entry> {
+    read_bundled(BUNDLED_ANSWERS, ListFormat::Tsv, count)
+}
+
+/// every guess compiled into the binary
+pub fn bundled_guesses() -> Vec<String> {
+    read_bundled(BUNDLED_GUESSES, ListFormat::Plain, u64::MAX)
+        .into_iter()
+        .map(|entry| entry.word)
+        .collect()
+}
+
+fn read_bundled(list: &str, format: ListFormat, count: u64) -> Vec<Entry> {
+    read_words(list.as_bytes(), Some(format), DEFAULT_LENGTH, count).expect("bundled list is valid")
+}
+
+/// words that may be the answer and words that may be guessed
+///
+/// `guesses` starts with every answer in the same order, so answer `i` is
+/// also guess `i`, followed by the allowed guesses that aren't answers
+#[derive(Debug, Clone, Default)]
+pub struct WordLists {
+    pub answers: Vec<String>,
+    pub guesses: Vec<String>,
+    /// prior weight of each answer, relative to the others
+    pub weights: Vec<f64>,
+    /// row of each word in `guesses`
+    index: HashMap<String, usize>,
+}
+
+impl WordLists {
+    /// combines an answer list with a list of extra allowed guesses
+    pub fn new(answers: Vec<String>, allowed: Vec<String>) -> WordLists {
+        let known: HashSet<&String> = answers.iter().collect();
+        let extra: Vec<String> = allowed
+            .iter()
+            .filter(|w| !known.contains(w))
+            .cloned()
+            .collect();
+        let mut guesses = answers.clone();
+        guesses.extend(extra);
+        // first row wins if a list repeats a word
+        let mut index = HashMap::new();
+        for (i, word) in guesses.iter().enumerate() {
+            index.entry(word.clone()).or_insert(i);
+        }
+        WordLists {
+            weights: vec![1.0; answers.len()],
+            answers,
+            guesses,
+            index,
+        }
+    }
+
+    /// weighs the answers using their corpus counts, given in answer order
+    pub fn set_prior(&mut self, prior: Prior, counts: &[Option<u64>]) {
+        self.weights = prior.weights(counts);
+    }
+
+    /// probability of each candidate being the answer under the prior
+    pub fn probabilities(&self, candidates: &[usize]) -> Vec<f64> {
+        let total: f64 = candidates.iter().map(|&c| self.weights[c]).sum();
+        candidates
+            .iter()
+            .map(|&c| self.weights[c] / total)
+            .collect()
+    }
+
+    /// row of a word in the guess list, if it may be guessed
+    pub fn guess_index(&self, word: &str) -> Option<usize> {
+        self.index.get(word).copied()
+    }
+
+    /// uses one list for both answers and guesses
+    pub fn single(words: Vec<String>) -> WordLists {
+        WordLists::new(words, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::process;
+
+    #[test]
+    fn reads_gzip_compressed_lists() {
+        let path = env::temp_dir().join(format!("wordle-test-{}.tsv.gz", process::id()));
+        let mut gz = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        gz.write_all(b"about\t1226\nother\t978\nthe\t2313\n")
+            .unwrap();
+        gz.finish().unwrap();
+
+        let entries = parse_entries(&path, None, 5, u64::MAX);
+        fs::remove_file(&path).unwrap();
+        let words: Vec<String> = entries.unwrap().into_iter().map(|e| e.word).collect();
+        assert_eq!(words, vec!["about", "other"]);
+    }
+    #[test]
+    fn finds_guess_rows() {
+        let answers = vec!["about".to_string(), "other".to_string()];
+        let allowed = vec!["aahed".to_string(), "about".to_string()];
+        let words = WordLists::new(answers, allowed);
+        assert_eq!(words.guesses, vec!["about", "other", "aahed"]);
+        assert_eq!(words.guess_index("other"), Some(1));
+        assert_eq!(words.guess_index("aahed"), Some(2));
+        assert_eq!(words.guess_index("zzzzz"), None);
+    }
+}